@@ -0,0 +1,116 @@
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, Normal};
+
+use crate::{chromosome::Chromosome, MutationContext, MutationMethod};
+
+/// Mutation that gets weaker as the run goes on: touched genes get noise
+/// from a normal distribution whose standard deviation starts at
+/// `coefficient` and gets multiplied by `decay` each generation, until it
+/// reaches `min_coefficient` - so early generations explore, and later ones
+/// fine-tune.
+#[derive(Clone, Debug)]
+pub struct AnnealedMutation {
+    /// probability of mutating each gene
+    chance: f32,
+
+    /// Standard deviation of mutation during the first generation
+    coefficient: f32,
+
+    /// How much the standard deviation shrinks per generation
+    decay: f32,
+
+    /// Standard deviation never goes below this value
+    min_coefficient: f32,
+}
+
+impl AnnealedMutation {
+    pub fn new(chance: f32, coefficient: f32, decay: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(coefficient >= 0.0);
+        assert!(decay > 0.0 && decay <= 1.0);
+
+        Self {
+            chance,
+            coefficient,
+            decay,
+            min_coefficient: 0.0,
+        }
+    }
+
+    pub fn with_min_coefficient(mut self, min_coefficient: f32) -> Self {
+        assert!(min_coefficient >= 0.0);
+
+        self.min_coefficient = min_coefficient;
+        self
+    }
+
+    /// Standard deviation of mutation during given generation
+    pub fn coefficient(&self, generation: usize) -> f32 {
+        (self.coefficient * self.decay.powf(generation as f32)).max(self.min_coefficient)
+    }
+}
+
+impl MutationMethod for AnnealedMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome, context: &MutationContext) {
+        let coefficient = self.coefficient(context.generation);
+
+        if coefficient == 0.0 {
+            return;
+        }
+
+        let noise = Normal::new(0.0, coefficient).unwrap();
+
+        child.iter_mut().for_each(|gene| {
+            if rng.gen_bool(self.chance as _) {
+                *gene += noise.sample(rng);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn std_dev(method: &AnnealedMutation, generation: usize) -> f32 {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child: Chromosome = vec![0.0; 10_000].into_iter().collect();
+
+        let context = MutationContext {
+            generation,
+            ..Default::default()
+        };
+
+        method.mutate(&mut rng, &mut child, &context);
+
+        (child.iter().map(|gene| gene.powi(2)).sum::<f32>() / child.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_coefficient() {
+        let method = AnnealedMutation::new(1.0, 2.0, 0.5).with_min_coefficient(0.1);
+
+        approx::assert_relative_eq!(method.coefficient(0), 2.0);
+        approx::assert_relative_eq!(method.coefficient(1), 1.0);
+        approx::assert_relative_eq!(method.coefficient(3), 0.25);
+        approx::assert_relative_eq!(method.coefficient(10), 0.1);
+    }
+
+    #[test]
+    fn test_decay() {
+        let method = AnnealedMutation::new(1.0, 2.0, 0.5);
+
+        approx::assert_relative_eq!(std_dev(&method, 0), 2.0, epsilon = 0.05);
+        approx::assert_relative_eq!(std_dev(&method, 2), 0.5, epsilon = 0.0125);
+        approx::assert_relative_eq!(std_dev(&method, 200), 0.0);
+    }
+
+    #[test]
+    fn test_chance() {
+        let method = AnnealedMutation::new(0.0, 2.0, 0.5);
+
+        approx::assert_relative_eq!(std_dev(&method, 0), 0.0);
+    }
+}
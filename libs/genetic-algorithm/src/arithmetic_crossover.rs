@@ -0,0 +1,85 @@
+use rand::{Rng, RngCore};
+
+use crate::{chromosome::Chromosome, CrossoverMethod};
+
+/// Blends both parents gene by gene: `child = alpha * a + (1 - alpha) * b`.
+///
+/// `alpha` is either fixed (`new()`) or drawn from `0.0..=1.0` for each
+/// child separately (`random()`).
+#[derive(Clone, Debug)]
+pub struct ArithmeticCrossover {
+    alpha: Option<f32>,
+}
+
+impl ArithmeticCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!((0.0..=1.0).contains(&alpha));
+
+        Self { alpha: Some(alpha) }
+    }
+
+    pub fn random() -> Self {
+        Self { alpha: None }
+    }
+}
+
+impl CrossoverMethod for ArithmeticCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(
+            parent_a.len(),
+            parent_b.len(),
+            "Both parents have to be in the same length"
+        );
+
+        let alpha = self.alpha.unwrap_or_else(|| rng.gen_range(0.0..=1.0));
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| alpha * a + (1.0 - alpha) * b)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn parents() -> (Chromosome, Chromosome) {
+        (
+            vec![1.0, 2.0, 3.0, 4.0].into_iter().collect(),
+            vec![-3.0, 2.0, 7.0, 0.0].into_iter().collect(),
+        )
+    }
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let (parent_a, parent_b) = parents();
+
+        let child = ArithmeticCrossover::new(0.25).crossover(&mut rng, &parent_a, &parent_b);
+        let expected: Chromosome = vec![-2.0, 2.0, 6.0, 1.0].into_iter().collect();
+
+        assert_eq!(child, expected);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let (parent_a, parent_b) = parents();
+
+        let child = ArithmeticCrossover::random().crossover(&mut rng, &parent_a, &parent_b);
+        let expected: Chromosome = vec![-2.2510376, 2.0, 6.2510376, 0.74896246]
+            .into_iter()
+            .collect();
+
+        assert_eq!(child, expected);
+    }
+}
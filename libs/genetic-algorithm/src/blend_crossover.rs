@@ -0,0 +1,97 @@
+use rand::{Rng, RngCore};
+
+use crate::{chromosome::Chromosome, CrossoverMethod};
+
+/// BLX-α: each gene of the child is drawn uniformly from the range spanned
+/// by the parents' genes, extended by `alpha` times its width on both
+/// sides - so children can land between their parents, and a bit beyond.
+///
+/// `alpha = 0.0` keeps children strictly between parents, while `0.5` (the
+/// usual choice) keeps the population's spread roughly stable.
+#[derive(Clone, Debug)]
+pub struct BlendCrossover {
+    alpha: f32,
+}
+
+impl BlendCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha >= 0.0);
+
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for BlendCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(
+            parent_a.len(),
+            parent_b.len(),
+            "Both parents have to be in the same length"
+        );
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let (min, max) = if a < b { (a, b) } else { (b, a) };
+                let extent = self.alpha * (max - min);
+
+                if max > min {
+                    rng.gen_range((min - extent)..=(max + extent))
+                } else {
+                    min
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let parent_a: Chromosome = vec![1.0, 2.0, -3.0, 4.0].into_iter().collect();
+        let parent_b: Chromosome = vec![3.0, 2.0, 1.0, -4.0].into_iter().collect();
+
+        let child = BlendCrossover::new(0.5).crossover(&mut rng, &parent_a, &parent_b);
+        let expected: Chromosome = vec![0.74896246, 2.0, 1.6953583, 6.5450096]
+            .into_iter()
+            .collect();
+
+        assert_eq!(child, expected);
+    }
+
+    #[test]
+    fn test_spread() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let parent_a: Chromosome = vec![-1.0; 10_000].into_iter().collect();
+        let parent_b: Chromosome = vec![1.0; 10_000].into_iter().collect();
+
+        for &alpha in &[0.0, 0.5] {
+            let child = BlendCrossover::new(alpha).crossover(&mut rng, &parent_a, &parent_b);
+
+            // Parents are two apart, so genes are uniform on
+            // `-(1 + 2 * alpha)..=(1 + 2 * alpha)`
+            let limit = 1.0 + 2.0 * alpha;
+            let mean = child.iter().sum::<f32>() / child.len() as f32;
+            let variance = child.iter().map(|gene| gene.powi(2)).sum::<f32>() / child.len() as f32;
+
+            assert!(child.iter().all(|gene| gene.abs() <= limit));
+            assert!(child.iter().any(|gene| gene.abs() > 0.99 * limit));
+            approx::assert_relative_eq!(mean, 0.0, epsilon = 0.03);
+            approx::assert_relative_eq!(variance, limit * limit / 3.0, epsilon = 0.03);
+        }
+    }
+}
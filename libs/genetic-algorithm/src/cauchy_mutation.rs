@@ -0,0 +1,70 @@
+use rand::{Rng, RngCore};
+use rand_distr::{Cauchy, Distribution};
+
+use crate::{chromosome::Chromosome, MutationContext, MutationMethod};
+
+/// Adds noise from a Cauchy distribution centered at zero to touched genes;
+/// half of the changes stay within `scale`, but the tails are so heavy that
+/// every now and then a gene jumps far away, which helps escaping local
+/// optima.
+#[derive(Clone, Debug)]
+pub struct CauchyMutation {
+    /// probability of mutating each gene
+    chance: f32,
+
+    /// Median of the noise's magnitude
+    scale: f32,
+}
+
+impl CauchyMutation {
+    pub fn new(chance: f32, scale: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(scale > 0.0);
+
+        Self { chance, scale }
+    }
+}
+
+impl MutationMethod for CauchyMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome, _: &MutationContext) {
+        let noise = Cauchy::new(0.0, self.scale).unwrap();
+
+        child.iter_mut().for_each(|gene| {
+            if rng.gen_bool(self.chance as _) {
+                *gene += noise.sample(rng);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_distribution() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child: Chromosome = vec![0.0; 10_000].into_iter().collect();
+
+        CauchyMutation::new(1.0, 0.5).mutate(&mut rng, &mut child, &Default::default());
+
+        let fraction = |limit: f32| {
+            child.iter().filter(|gene| gene.abs() > limit).count() as f32 / child.len() as f32
+        };
+
+        // P(|X| > x) = 1 - 2 / pi * atan(x / scale)
+        for &limit in &[0.5f32, 2.0, 5.0, 50.0] {
+            let expected = 1.0 - 2.0 / PI * (limit / 0.5).atan();
+
+            approx::assert_relative_eq!(fraction(limit), expected, epsilon = 0.015);
+        }
+
+        // ... which is way more than normal distribution with similar spread
+        // would ever give
+        assert!(fraction(5.0) > 0.05);
+    }
+}
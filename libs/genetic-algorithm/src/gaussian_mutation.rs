@@ -1,7 +1,10 @@
 use rand::{Rng, RngCore};
 
-use crate::{chromosome::Chromosome, MutationMethod};
+use crate::{chromosome::Chromosome, MutationContext, MutationMethod};
 
+/// Adds a random amount of at most `coefficient` to touched genes, drawn
+/// uniformly despite the name; see `NormalMutation` for the normally
+/// distributed one.
 #[derive(Clone, Debug)]
 pub struct GaussianMutation {
     /// probability of mutating:
@@ -27,7 +30,7 @@ impl GaussianMutation {
 }
 
 impl MutationMethod for GaussianMutation {
-    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome, _: &MutationContext) {
         child.iter_mut().for_each(|gene| {
             let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
 
@@ -49,7 +52,11 @@ mod tests {
 
         let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-        GaussianMutation::new(chance, coefficient).mutate(&mut rng, &mut child);
+        GaussianMutation::new(chance, coefficient).mutate(
+            &mut rng,
+            &mut child,
+            &MutationContext::default(),
+        );
 
         child.into_iter().collect()
     }
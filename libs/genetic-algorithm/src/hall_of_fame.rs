@@ -0,0 +1,227 @@
+use crate::{chromosome::Chromosome, Individual};
+
+/// A chromosome that has made it into `HallOfFame`.
+#[derive(Clone, Debug)]
+pub struct Champion {
+    chromosome: Chromosome,
+    fitness: f32,
+    generation: usize,
+}
+
+impl Champion {
+    pub fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+
+    pub fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    /// Generation the chromosome was born in
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+
+/// The `capacity` fittest unique chromosomes seen so far, kept from the
+/// fittest one - so that a lucky champion isn't lost once its generation
+/// is replaced.
+///
+/// When the same chromosome shows up again (e.g. thanks to elitism), it
+/// keeps a single entry with its highest fitness and the generation it was
+/// first seen in.
+#[derive(Clone, Debug)]
+pub struct HallOfFame {
+    capacity: usize,
+    champions: Vec<Champion>,
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "hall of fame needs room for someone");
+
+        Self {
+            capacity,
+            champions: Vec::with_capacity(capacity + 1),
+        }
+    }
+
+    /// Considers every individual of a population born in `generation`;
+    /// returns how many of them made it in
+    pub fn update<I>(&mut self, population: &[I], generation: usize) -> usize
+    where
+        I: Individual,
+    {
+        population
+            .iter()
+            .filter(|individual| {
+                self.insert(individual.chromosome(), individual.fitness(), generation)
+            })
+            .count()
+    }
+
+    /// Records a chromosome if it's fit enough; returns whether it's in
+    pub fn insert(&mut self, chromosome: &Chromosome, fitness: f32, generation: usize) -> bool {
+        if fitness.is_nan() {
+            return false;
+        }
+
+        if let Some(idx) = self
+            .champions
+            .iter()
+            .position(|champion| champion.chromosome.iter().eq(chromosome.iter()))
+        {
+            let champion = self.champions.remove(idx);
+
+            self.place(Champion {
+                fitness: champion.fitness.max(fitness),
+                generation: champion.generation.min(generation),
+                ..champion
+            });
+
+            return true;
+        }
+
+        if self.champions.len() == self.capacity
+            && matches!(self.champions.last(), Some(worst) if worst.fitness >= fitness)
+        {
+            return false;
+        }
+
+        self.place(Champion {
+            chromosome: chromosome.clone(),
+            fitness,
+            generation,
+        });
+
+        self.champions.truncate(self.capacity);
+
+        true
+    }
+
+    /// Inserts champion after everyone at least as fit, so that older
+    /// entries win ties
+    fn place(&mut self, champion: Champion) {
+        let idx = self
+            .champions
+            .iter()
+            .position(|other| other.fitness < champion.fitness)
+            .unwrap_or(self.champions.len());
+
+        self.champions.insert(idx, champion);
+    }
+
+    pub fn best(&self) -> Option<&Champion> {
+        self.champions.first()
+    }
+
+    /// Champions from the fittest one
+    pub fn champions(&self) -> &[Champion] {
+        &self.champions
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.champions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.champions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::individual;
+
+    fn summary(hall_of_fame: &HallOfFame) -> Vec<(Vec<f32>, f32, usize)> {
+        hall_of_fame
+            .champions()
+            .iter()
+            .map(|champion| {
+                (
+                    champion.chromosome().iter().copied().collect(),
+                    champion.fitness(),
+                    champion.generation(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test() {
+        let mut hall_of_fame = HallOfFame::new(3);
+
+        let added = hall_of_fame.update(
+            &[
+                individual(&[1.0, 1.0]), // fitness: 2.0
+                individual(&[0.0, 0.0]), // fitness: 0.0
+                individual(&[3.0, 1.0]), // fitness: 4.0
+                individual(&[1.0, 0.0]), // fitness: 1.0
+            ],
+            0,
+        );
+
+        assert_eq!(added, 4);
+
+        assert_eq!(
+            summary(&hall_of_fame),
+            vec![
+                (vec![3.0, 1.0], 4.0, 0),
+                (vec![1.0, 1.0], 2.0, 0),
+                (vec![1.0, 0.0], 1.0, 0),
+            ]
+        );
+
+        let added = hall_of_fame.update(
+            &[
+                individual(&[0.5, 0.0]), // fitness: 0.5
+                individual(&[2.0, 1.0]), // fitness: 3.0
+                individual(&[1.0, 1.0]), // fitness: 2.0 - already in
+            ],
+            1,
+        );
+
+        assert_eq!(added, 2);
+
+        assert_eq!(
+            summary(&hall_of_fame),
+            vec![
+                (vec![3.0, 1.0], 4.0, 0),
+                (vec![2.0, 1.0], 3.0, 1),
+                (vec![1.0, 1.0], 2.0, 0),
+            ]
+        );
+
+        assert_eq!(hall_of_fame.best().unwrap().fitness(), 4.0);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut hall_of_fame = HallOfFame::new(2);
+        let chromosome: Chromosome = vec![1.0, 2.0].into_iter().collect();
+
+        assert!(hall_of_fame.insert(&chromosome, 3.0, 2));
+        assert!(hall_of_fame.insert(&chromosome, 5.0, 4));
+        assert!(hall_of_fame.insert(&chromosome, 1.0, 7));
+
+        assert_eq!(summary(&hall_of_fame), vec![(vec![1.0, 2.0], 5.0, 2)]);
+    }
+
+    #[test]
+    fn test_ties() {
+        let mut hall_of_fame = HallOfFame::new(2);
+
+        hall_of_fame.update(&[individual(&[1.0]), individual(&[0.5, 0.5])], 0);
+        hall_of_fame.update(&[individual(&[0.25, 0.75])], 1);
+
+        assert_eq!(
+            summary(&hall_of_fame),
+            vec![(vec![1.0], 1.0, 0), (vec![0.5, 0.5], 1.0, 0)]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use rand::{seq::index, RngCore};
+
+use crate::{chromosome::Chromosome, CrossoverMethod};
+
+/// Cuts both parents at `points` random places (the same for both) and
+/// builds the child from alternating pieces, starting with `parent_a`.
+#[derive(Clone, Debug)]
+pub struct KPointCrossover {
+    points: usize,
+}
+
+impl KPointCrossover {
+    pub fn new(points: usize) -> Self {
+        assert!(points > 0, "Crossover needs at least one point");
+
+        Self { points }
+    }
+}
+
+impl CrossoverMethod for KPointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(
+            parent_a.len(),
+            parent_b.len(),
+            "Both parents have to be in the same length"
+        );
+
+        assert!(
+            self.points < parent_a.len(),
+            "Parents need more genes than there are crossover points"
+        );
+
+        // Points fall between genes, i.e. within `1..len`
+        let mut points: Vec<usize> = index::sample(rng, parent_a.len() - 1, self.points)
+            .into_iter()
+            .map(|point| point + 1)
+            .collect();
+
+        points.sort_unstable();
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .enumerate()
+            .map(|(idx, (&a, &b))| {
+                let crossed = points.iter().filter(|&&point| point <= idx).count();
+
+                if crossed % 2 == 0 {
+                    a
+                } else {
+                    b
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn child(points: usize) -> Chromosome {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let parent_a: Chromosome = (1..=10).map(|n| n as f32).collect();
+        let parent_b: Chromosome = (1..=10).map(|n| -n as f32).collect();
+
+        KPointCrossover::new(points).crossover(&mut rng, &parent_a, &parent_b)
+    }
+
+    #[test]
+    fn test() {
+        let expected: Chromosome = vec![1.0, 2.0, -3.0, 4.0, 5.0, 6.0, 7.0, 8.0, -9.0, -10.0]
+            .into_iter()
+            .collect();
+
+        assert_eq!(child(3), expected);
+    }
+
+    #[test]
+    fn test_every_gene() {
+        let expected: Chromosome = vec![1.0, -2.0, 3.0, -4.0, 5.0, -6.0, 7.0, -8.0, 9.0, -10.0]
+            .into_iter()
+            .collect();
+
+        assert_eq!(child(9), expected);
+    }
+}
@@ -0,0 +1,93 @@
+use rand::{Rng, RngCore};
+
+use crate::{chromosome::Chromosome, CrossoverMethod};
+
+/// Splits chromosomes into consecutive segments of given lengths (e.g. the
+/// weights of each neural network layer, as returned by
+/// `Network::weights_per_layer()`) and takes each segment as a whole from a
+/// randomly chosen parent, so that genes working together stay together.
+#[derive(Clone, Debug)]
+pub struct LayerCrossover {
+    layout: Vec<usize>,
+}
+
+impl LayerCrossover {
+    pub fn new(layout: Vec<usize>) -> Self {
+        assert!(!layout.is_empty(), "Layout needs at least one segment");
+
+        Self { layout }
+    }
+
+    pub fn layout(&self) -> &[usize] {
+        &self.layout
+    }
+}
+
+impl CrossoverMethod for LayerCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(
+            parent_a.len(),
+            parent_b.len(),
+            "Both parents have to be in the same length"
+        );
+
+        assert_eq!(
+            parent_a.len(),
+            self.layout.iter().sum::<usize>(),
+            "Parents have to match the layout"
+        );
+
+        let mut child = Vec::with_capacity(parent_a.len());
+        let mut start = 0;
+
+        for &len in &self.layout {
+            let parent = if rng.gen_bool(0.5) {
+                parent_a
+            } else {
+                parent_b
+            };
+
+            child.extend(parent.iter().skip(start).take(len));
+            start += len;
+        }
+
+        child.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let parent_a: Chromosome = (1..=10).map(|n| n as f32).collect();
+        let parent_b: Chromosome = (1..=10).map(|n| -n as f32).collect();
+
+        let child = LayerCrossover::new(vec![3, 1, 4, 2]).crossover(&mut rng, &parent_a, &parent_b);
+
+        let expected: Chromosome = vec![-1.0, -2.0, -3.0, -4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]
+            .into_iter()
+            .collect();
+
+        assert_eq!(child, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_layout_mismatch() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let parent: Chromosome = (1..=10).map(|n| n as f32).collect();
+
+        LayerCrossover::new(vec![3, 3]).crossover(&mut rng, &parent, &parent);
+    }
+}
@@ -1,12 +1,33 @@
 #![feature(min_type_alias_impl_trait)]
 
 use chromosome::Chromosome;
+use hall_of_fame::HallOfFame;
 use rand::RngCore;
+use replacement::Replacement;
 use statistics::Statistics;
 
+pub mod annealed_mutation;
+pub mod arithmetic_crossover;
+pub mod blend_crossover;
+pub mod cauchy_mutation;
 pub mod chromosome;
 pub mod gaussian_mutation;
+pub mod hall_of_fame;
+pub mod k_point_crossover;
+pub mod layer_crossover;
+pub mod neat;
+pub mod normal_mutation;
+pub mod one_fifth_rule_mutation;
+pub mod polynomial_mutation;
+pub mod rank;
+pub mod replacement;
 pub mod roulette_wheel;
+pub mod self_adaptive_mutation;
+pub mod simulated_binary_crossover;
+pub mod single_point_crossover;
+pub mod steady_state;
+pub mod stochastic_universal_sampling;
+pub mod tournament;
 pub mod uniform_crossover;
 pub mod statistics;
 
@@ -20,6 +41,21 @@ pub trait SelectionMethod {
     fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
     where
         I: Individual;
+
+    /// Selects `count` individuals at once; by default it just calls
+    /// `select()` that many times, but methods that need to see the whole
+    /// batch (e.g. `StochasticUniversalSampling`) override it
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        (0..count).map(|_| self.select(rng, population)).collect()
+    }
 }
 
 pub trait CrossoverMethod {
@@ -32,13 +68,47 @@ pub trait CrossoverMethod {
 }
 
 pub trait MutationMethod {
-    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome, context: &MutationContext);
+
+    /// Called once per generation, before any of its children get mutated,
+    /// so that methods such as `OneFifthRuleMutation` can adjust themselves;
+    /// does nothing by default
+    fn adapt(&mut self, _context: &MutationContext) {}
+}
+
+/// What mutation methods get to know about the run they're part of, e.g.
+/// so that they can adjust their strength over time
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MutationContext {
+    /// Number of generations (or steady-state steps) evolved so far
+    pub generation: usize,
+
+    /// Fraction of the previous generation's children that turned out
+    /// fitter than the fitter of their parents; `None` when unknown, e.g.
+    /// during the first generation
+    pub success_rate: Option<f32>,
 }
 
 pub struct GeneticAlgorithm<S> {
     selection_method: S,
     crossover_method: Box<dyn CrossoverMethod>,
     mutation_method: Box<dyn MutationMethod>,
+    elitism: usize,
+    replacement: Replacement,
+    offspring: Option<usize>,
+    generation: usize,
+
+    /// Number of individuals surviving each generation (μ) for
+    /// `Replacement::Plus` and `Replacement::Comma`; remembered during the
+    /// first `evolve()`
+    parents: Option<usize>,
+
+    /// For each individual returned by the last `evolve()`, fitness of its
+    /// fitter parent (`None` for those that weren't bred)
+    parent_fitness: Vec<Option<f32>>,
+
+    hall_of_fame: Option<HallOfFame>,
+    diversity: bool,
 }
 
 impl<S> GeneticAlgorithm<S>
@@ -54,40 +124,260 @@ where
             selection_method,
             crossover_method: Box::new(crossover_method),
             mutation_method: Box::new(mutation_method),
+            elitism: 0,
+            replacement: Replacement::default(),
+            offspring: None,
+            generation: 0,
+            parents: None,
+            parent_fitness: Vec::new(),
+            hall_of_fame: None,
+            diversity: false,
         }
     }
 
-    pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    /// Carries `elitism` fittest individuals over to the next generation
+    /// unchanged
+    pub fn with_elitism(mut self, elitism: usize) -> Self {
+        self.elitism = elitism;
+        self
+    }
+
+    pub fn with_replacement(mut self, replacement: Replacement) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    /// Number of children bred each generation; defaults to the population's
+    /// size (μ, for `Replacement::Plus` and `Replacement::Comma`) minus
+    /// `elitism`
+    pub fn with_offspring(mut self, offspring: usize) -> Self {
+        self.offspring = Some(offspring);
+        self
+    }
+
+    /// Makes `evolve()` keep track of `capacity` fittest chromosomes it has
+    /// ever seen
+    pub fn with_hall_of_fame(mut self, capacity: usize) -> Self {
+        self.hall_of_fame = Some(HallOfFame::new(capacity));
+        self
+    }
+
+    /// Makes `evolve()` measure the population's `Diversity` too; it's off
+    /// by default, since it compares each pair of chromosomes
+    pub fn with_diversity(mut self, diversity: bool) -> Self {
+        self.diversity = diversity;
+        self
+    }
+
+    pub fn hall_of_fame(&self) -> Option<&HallOfFame> {
+        self.hall_of_fame.as_ref()
+    }
+
+    /// Number of generations evolved so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Expects `population` to be what the previous call returned (with
+    /// fitness evaluated in the meantime) - otherwise mutation methods won't
+    /// get to know the success rate, and `Replacement::Comma` won't know
+    /// which individuals are children
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
     where
         I: Individual,
     {
         assert!(!population.is_empty());
 
-        let new_population = (0..population.len())
-            .map(|_| {
-                let parent_a = self.selection_method.select(rng, population).chromosome();
-                let parent_b = self.selection_method.select(rng, population).chromosome();
-                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+        let parents = match self.replacement {
+            Replacement::Generational => population.len(),
+            Replacement::Plus | Replacement::Comma => {
+                *self.parents.get_or_insert(population.len())
+            }
+        };
 
-                self.mutation_method.mutate(rng, &mut child);
+        assert!(
+            self.elitism <= parents.min(population.len()),
+            "elitism exceeds population's size"
+        );
 
-                I::from_chromosome(child)
-            })
+        let survivors = parents - self.elitism;
+        let offspring = self.offspring.unwrap_or(survivors);
+
+        assert!(
+            self.elitism + offspring > 0,
+            "generation would be empty: there's neither elitism nor offspring"
+        );
+
+        if self.replacement == Replacement::Comma {
+            assert!(
+                offspring >= survivors,
+                "(μ,λ) replacement needs at least {} children, got {}",
+                survivors,
+                offspring
+            );
+        }
+
+        // Individuals bred by the previous call; if `population` isn't what
+        // it has returned, all of them are treated as children
+        let bred: Vec<_> = if population.len() == self.parent_fitness.len() {
+            self.parent_fitness.iter().map(Option::is_some).collect()
+        } else {
+            vec![true; population.len()]
+        };
+
+        if let Some(hall_of_fame) = &mut self.hall_of_fame {
+            hall_of_fame.update(population, self.generation);
+        }
+
+        let context = MutationContext {
+            generation: self.generation,
+            success_rate: self.success_rate(population),
+        };
+
+        self.adapt(&context);
+
+        let children: Vec<(I, Option<f32>)> = self
+            .offspring(rng, population, offspring, &context)
+            .into_iter()
+            .map(|(child, parent_fitness)| (child, Some(parent_fitness)))
             .collect();
 
-            let stats = Statistics::new(population);
+        let mut ranked: Vec<(&I, bool)> = population.iter().zip(bred).collect();
+        replacement::rank(&mut ranked, |(individual, _)| individual.fitness());
+
+        let (elites, rest) = ranked.split_at(self.elitism);
+        let copy = |(individual, _): &(&I, bool)| {
+            (I::from_chromosome(individual.chromosome().clone()), None)
+        };
+        let mut new_population: Vec<_> = elites.iter().map(copy).collect();
+
+        match self.replacement {
+            Replacement::Generational => (),
+
+            Replacement::Plus => {
+                new_population.extend(rest.iter().take(survivors).map(copy));
+            }
+
+            Replacement::Comma => {
+                new_population.extend(
+                    rest.iter()
+                        .filter(|(_, bred)| *bred)
+                        .take(survivors)
+                        .map(copy),
+                );
+            }
+        }
+
+        new_population.extend(children);
+
+        let (new_population, parent_fitness) = new_population.into_iter().unzip();
+
+        self.parent_fitness = parent_fitness;
+        self.generation += 1;
+
+            let stats = if self.diversity {
+                Statistics::with_diversity(population)
+            } else {
+                Statistics::new(population)
+            };
+
             (new_population, stats)
     }
+
+    /// Selects parents, crosses them over and mutates the results, returning
+    /// `count` children; this is what `evolve()` and `SteadyState` build new
+    /// generations from, and it can be used directly, e.g. to replace an
+    /// individual as soon as it dies.
+    ///
+    /// Unlike those two, it doesn't call `MutationMethod::adapt()` - see
+    /// `adapt()`
+    pub fn breed<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        count: usize,
+        context: &MutationContext,
+    ) -> Vec<I>
+    where
+        I: Individual,
+    {
+        self.offspring(rng, population, count, context)
+            .into_iter()
+            .map(|(child, _)| child)
+            .collect()
+    }
+
+    /// Lets the mutation method adjust itself to a new generation; call it
+    /// once per generation when breeding children with `breed()`
+    pub fn adapt(&mut self, context: &MutationContext) {
+        self.mutation_method.adapt(context);
+    }
+
+    /// Like `breed()`, but also returns fitness of each child's fitter parent
+    fn offspring<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        count: usize,
+        context: &MutationContext,
+    ) -> Vec<(I, f32)>
+    where
+        I: Individual,
+    {
+        let parents = self
+            .selection_method
+            .select_many(rng, population, 2 * count);
+
+        parents
+            .chunks(2)
+            .map(|parents| {
+                let parent_a = parents[0].chromosome();
+                let parent_b = parents[1].chromosome();
+                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+
+                self.mutation_method.mutate(rng, &mut child, context);
+
+                let parent_fitness = parents[0].fitness().max(parents[1].fitness());
+
+                (I::from_chromosome(child), parent_fitness)
+            })
+            .collect()
+    }
+
+    fn success_rate<I>(&self, population: &[I]) -> Option<f32>
+    where
+        I: Individual,
+    {
+        if population.len() != self.parent_fitness.len() {
+            return None;
+        }
+
+        let (bred, successful) = population
+            .iter()
+            .zip(&self.parent_fitness)
+            .filter_map(|(individual, parent_fitness)| {
+                parent_fitness.map(|parent_fitness| individual.fitness() > parent_fitness)
+            })
+            .fold((0, 0), |(bred, successful), success| {
+                (bred + 1, successful + success as usize)
+            });
+
+        if bred == 0 {
+            None
+        } else {
+            Some(successful as f32 / bred as f32)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::BTreeMap, iter::FromIterator};
+    use std::{cell::RefCell, collections::BTreeMap, iter::FromIterator, rc::Rc};
 
     use crate::{
         chromosome::Chromosome, gaussian_mutation::GaussianMutation,
-        roulette_wheel::RouletteWheelSelection, uniform_crossover::UniformCrossover,
-        GeneticAlgorithm,
+        roulette_wheel::RouletteWheelSelection, tournament::TournamentSelection,
+        uniform_crossover::UniformCrossover, GeneticAlgorithm,
     };
 
     use super::*;
@@ -158,7 +448,7 @@ mod tests {
         assert_eq!(actual_histogram, expected_histogram);
     }
 
-    fn individual(genes: &[f32]) -> TestIndividual {
+    pub(crate) fn individual(genes: &[f32]) -> TestIndividual {
         let chromosome = genes.iter().cloned().collect();
         TestIndividual::from_chromosome(chromosome)
     }
@@ -167,7 +457,7 @@ mod tests {
     fn test_mutation() {
         let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-        let ga = GeneticAlgorithm::new(
+        let mut ga = GeneticAlgorithm::new(
             RouletteWheelSelection::new(),
             UniformCrossover::new(),
             GaussianMutation::new(0.5, 0.5),
@@ -185,12 +475,278 @@ mod tests {
         }
 
         let expected_population = vec![
-            individual(&[1.606008, 2.789879, 3.6941864]), // fitness: ~ 8.0
-            individual(&[1.0839049, 2.4461222, -0.8869108]), //fitness: ~ 7.1
-            individual(&[0.99193525, 2.588976, 3.5712361]), // fitness: ~ 8.0
-            individual(&[1.646358, 2.392836, 3.9752667]), // fitness: ~ 7.8
+            individual(&[1.1189791, 2.794279, 4.7044053]), // fitness: ~ 8.6
+            individual(&[0.68751615, 2.7635486, 4.7044053]), // fitness: ~ 8.2
+            individual(&[0.24016479, 2.7635486, 4.640434]), // fitness: ~ 7.6
+            individual(&[0.68751615, 3.0105753, 4.7290673]), // fitness: ~ 8.4
         ];
 
         assert_eq!(population, expected_population);
     }
+
+    fn ga() -> GeneticAlgorithm<TournamentSelection> {
+        GeneticAlgorithm::new(
+            TournamentSelection::new(2, 1.0),
+            UniformCrossover::new(),
+            GaussianMutation::new(1.0, 5.0),
+        )
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            individual(&[0.0, 0.0, 0.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[1.0, 2.0, 1.0]),
+            individual(&[1.0, 2.0, 4.0]),
+        ]
+    }
+
+    fn best_fitness(population: &[TestIndividual]) -> f32 {
+        population
+            .iter()
+            .map(Individual::fitness)
+            .fold(f32::MIN, f32::max)
+    }
+
+    #[test]
+    fn test_elitism() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = ga().with_elitism(1);
+        let mut population = population();
+
+        for _ in 0..10 {
+            let best = best_fitness(&population);
+            population = ga.evolve(&mut rng, &population).0;
+
+            assert_eq!(population.len(), 4);
+            assert!(best_fitness(&population) >= best);
+        }
+    }
+
+    fn fitness(population: &[TestIndividual]) -> Vec<f32> {
+        let mut fitness: Vec<_> = population.iter().map(Individual::fitness).collect();
+        fitness.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        fitness
+    }
+
+    #[test]
+    fn test_plus_replacement() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = ga().with_replacement(Replacement::Plus).with_offspring(6);
+        let mut population = population();
+
+        for _ in 0..10 {
+            let previous = population;
+            population = ga.evolve(&mut rng, &previous).0;
+
+            // The fittest four out of ten survive, children or not
+            assert_eq!(population.len(), 10);
+            assert_eq!(fitness(&population[..4]), fitness(&previous)[..4]);
+        }
+    }
+
+    #[test]
+    fn test_comma_replacement() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = ga().with_replacement(Replacement::Comma).with_offspring(8);
+        let mut population = population();
+
+        for _ in 0..10 {
+            let previous = population;
+            population = ga.evolve(&mut rng, &previous).0;
+
+            assert_eq!(population.len(), 12);
+
+            // Only the fittest four children survive; during the first
+            // generation all individuals count as children
+            let children = if previous.len() == 4 {
+                &previous[..]
+            } else {
+                &previous[4..]
+            };
+
+            assert_eq!(fitness(&population[..4]), fitness(children)[..4]);
+        }
+    }
+
+    /// Individual whose fitness is known only once it's evaluated, like it
+    /// is in `lib-simulation`
+    struct EvaluatedIndividual {
+        fitness: f32,
+        chromosome: Chromosome,
+    }
+
+    impl Individual for EvaluatedIndividual {
+        fn fitness(&self) -> f32 {
+            self.fitness
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+
+        fn from_chromosome(chromosome: Chromosome) -> Self {
+            Self {
+                fitness: 0.0,
+                chromosome,
+            }
+        }
+    }
+
+    #[test]
+    fn test_replacement_uses_evaluated_fitness() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        for &replacement in &[Replacement::Plus, Replacement::Comma] {
+            let mut ga = ga().with_replacement(replacement).with_offspring(4);
+
+            let population: Vec<_> = population()
+                .iter()
+                .map(|individual| EvaluatedIndividual {
+                    fitness: individual.fitness(),
+                    chromosome: individual.chromosome().clone(),
+                })
+                .collect();
+
+            let mut population = ga.evolve(&mut rng, &population).0;
+
+            // Evaluation shows the last child to be the fittest one
+            population[7].fitness = 100.0;
+
+            let actual = ga.evolve(&mut rng, &population).0;
+
+            assert_eq!(actual[0].chromosome, population[7].chromosome);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_comma_replacement_with_too_few_children() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = ga().with_replacement(Replacement::Comma).with_offspring(3);
+
+        ga.evolve(&mut rng, &population());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_no_offspring_without_elitism() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = ga().with_offspring(0);
+
+        ga.evolve(&mut rng, &population());
+    }
+
+    #[test]
+    fn test_diversity() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let (_, stats) = ga().evolve(&mut rng, &population());
+        assert!(stats.diversity().is_none());
+
+        let (_, stats) = ga()
+            .with_diversity(true)
+            .evolve(&mut rng, &population());
+
+        assert!(stats.diversity().unwrap().mean_distance() > 0.0);
+    }
+
+    #[test]
+    fn test_hall_of_fame() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = ga().with_hall_of_fame(2);
+        let mut population = population();
+        let mut best = f32::MIN;
+
+        for _ in 0..10 {
+            best = best.max(best_fitness(&population));
+            population = ga.evolve(&mut rng, &population).0;
+        }
+
+        let hall_of_fame = ga.hall_of_fame().unwrap();
+
+        assert_eq!(hall_of_fame.len(), 2);
+        assert_eq!(hall_of_fame.best().unwrap().fitness(), best);
+        assert!(hall_of_fame.champions()[1].fitness() <= best);
+    }
+
+    #[derive(Clone, Default)]
+    pub(crate) struct RecordingMutation {
+        pub(crate) contexts: Rc<RefCell<Vec<MutationContext>>>,
+        pub(crate) adaptations: Rc<RefCell<Vec<MutationContext>>>,
+    }
+
+    impl MutationMethod for RecordingMutation {
+        fn mutate(&self, _: &mut dyn RngCore, child: &mut Chromosome, context: &MutationContext) {
+            // Children of the first generation beat their parents, later
+            // ones don't
+            if context.generation == 0 {
+                child.iter_mut().for_each(|gene| *gene += 10.0);
+            }
+
+            self.contexts.borrow_mut().push(*context);
+        }
+
+        fn adapt(&mut self, context: &MutationContext) {
+            self.adaptations.borrow_mut().push(*context);
+        }
+    }
+
+    #[test]
+    fn test_mutation_context() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mutation = RecordingMutation::default();
+        let contexts = mutation.contexts.clone();
+        let adaptations = mutation.adaptations.clone();
+
+        let mut ga = GeneticAlgorithm::new(
+            TournamentSelection::new(2, 1.0),
+            UniformCrossover::new(),
+            mutation,
+        )
+        .with_elitism(1);
+
+        let mut population = population();
+
+        for _ in 0..3 {
+            population = ga.evolve(&mut rng, &population).0;
+        }
+
+        assert_eq!(ga.generation(), 3);
+
+        let expected: Vec<_> = vec![(0, None), (1, Some(1.0)), (2, Some(0.0))]
+            .into_iter()
+            .map(|(generation, success_rate)| MutationContext {
+                generation,
+                success_rate,
+            })
+            .collect();
+
+        // Three children per generation, next to one elite
+        let expected_contexts: Vec<_> = expected
+            .iter()
+            .flat_map(|&context| vec![context; 3])
+            .collect();
+
+        assert_eq!(*contexts.borrow(), expected_contexts);
+        assert_eq!(*adaptations.borrow(), expected);
+    }
+
+    #[test]
+    fn test_offspring() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let actual = ga().with_offspring(6).evolve(&mut rng, &population()).0;
+        assert_eq!(actual.len(), 6);
+
+        let actual = ga()
+            .with_elitism(2)
+            .with_offspring(1)
+            .evolve(&mut rng, &population())
+            .0;
+
+        assert_eq!(actual.len(), 3);
+        assert_eq!(actual[0], individual(&[1.0, 2.0, 4.0]));
+        assert_eq!(actual[1], individual(&[1.0, 2.0, 1.0]));
+    }
 }
@@ -0,0 +1,427 @@
+//! NEAT (NeuroEvolution of Augmenting Topologies) - evolves both the weights
+//! and the shape of networks, starting from the smallest possible ones.
+//!
+//! Unlike `GeneticAlgorithm`, it works on `Genome`s instead of `Chromosome`s
+//! and has to remember some things between generations (innovation numbers
+//! and species), which is why `Neat::evolve()` takes `&mut self`.
+
+use std::cmp::Ordering;
+
+use rand::{prelude::SliceRandom, Rng, RngCore};
+
+pub use self::{
+    genome::{ConnectionGene, Genome, NodeGene, NodeKind},
+    innovation::InnovationHistory,
+};
+use crate::statistics::Statistics;
+
+mod genome;
+mod innovation;
+
+pub trait NeatIndividual {
+    fn fitness(&self) -> f32;
+    fn genome(&self) -> &Genome;
+    fn from_genome(genome: Genome) -> Self;
+}
+
+#[derive(Clone, Debug)]
+pub struct NeatConfig {
+    /// Probability of nudging each weight and bias
+    pub weight_mutation_chance: f32,
+
+    /// Magnitude of nudging - same as in `GaussianMutation`
+    pub weight_mutation_coefficient: f32,
+
+    /// Probability of adding a hidden node to a child
+    pub add_node_chance: f32,
+
+    /// Probability of adding a connection to a child
+    pub add_connection_chance: f32,
+
+    /// Probability of a child being created by crossover rather than by just
+    /// cloning (and then mutating) its parent
+    pub crossover_chance: f32,
+
+    /// Genomes closer than this (see `Genome::distance()`) end up in the same
+    /// species
+    pub compatibility_threshold: f32,
+
+    pub excess_coefficient: f32,
+    pub disjoint_coefficient: f32,
+    pub weight_coefficient: f32,
+
+    /// Fraction of each species (the fittest ones) allowed to reproduce
+    pub survival_ratio: f32,
+
+    /// Species at least this large get their champion copied unchanged into
+    /// the next generation
+    pub elitism_species_size: usize,
+}
+
+impl Default for NeatConfig {
+    fn default() -> Self {
+        Self {
+            weight_mutation_chance: 0.8,
+            weight_mutation_coefficient: 0.5,
+            add_node_chance: 0.03,
+            add_connection_chance: 0.05,
+            crossover_chance: 0.75,
+            compatibility_threshold: 3.0,
+            excess_coefficient: 1.0,
+            disjoint_coefficient: 1.0,
+            weight_coefficient: 0.4,
+            survival_ratio: 0.2,
+            elitism_species_size: 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Species {
+    representative: Genome,
+    members: Vec<usize>,
+}
+
+impl Species {
+    pub fn representative(&self) -> &Genome {
+        &self.representative
+    }
+
+    /// Indices (into the population passed to `Neat::evolve()`) of genomes
+    /// belonging to this species
+    pub fn members(&self) -> &[usize] {
+        &self.members
+    }
+}
+
+pub struct Neat {
+    inputs: usize,
+    outputs: usize,
+    config: NeatConfig,
+    history: InnovationHistory,
+    species: Vec<Species>,
+}
+
+impl Neat {
+    pub fn new(inputs: usize, outputs: usize, config: NeatConfig) -> Self {
+        assert!(inputs > 0 && outputs > 0);
+
+        Self {
+            inputs,
+            outputs,
+            config,
+            history: InnovationHistory::new(inputs + outputs),
+            species: Vec::new(),
+        }
+    }
+
+    /// Creates a minimal genome to start the evolution with
+    pub fn random_genome(&mut self, rng: &mut dyn RngCore) -> Genome {
+        Genome::random(rng, &mut self.history, self.inputs, self.outputs)
+    }
+
+    /// Species found during the latest `evolve()`
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: NeatIndividual,
+    {
+        assert!(!population.is_empty());
+
+        self.speciate(population);
+
+        let offspring = self.offspring_counts(population);
+        let mut new_population = Vec::with_capacity(population.len());
+
+        for (species, offspring) in self.species.iter().zip(offspring) {
+            let mut members = species.members.clone();
+
+            members.sort_by(|&a, &b| {
+                population[b]
+                    .fitness()
+                    .partial_cmp(&population[a].fitness())
+                    .unwrap_or(Ordering::Equal)
+            });
+
+            let mut offspring = offspring;
+
+            if offspring > 0 && members.len() >= self.config.elitism_species_size {
+                new_population.push(I::from_genome(population[members[0]].genome().clone()));
+                offspring -= 1;
+            }
+
+            let survivors = ((members.len() as f32 * self.config.survival_ratio).ceil() as usize)
+                .max(1)
+                .min(members.len());
+
+            let survivors = &members[..survivors];
+
+            for _ in 0..offspring {
+                let a = rng.gen_range(0..survivors.len());
+
+                let mut child =
+                    if survivors.len() > 1 && rng.gen_bool(self.config.crossover_chance as _) {
+                        let b = rng.gen_range(0..survivors.len());
+
+                        // Survivors are sorted by fitness, so the smaller index
+                        // belongs to the fitter parent
+                        let fitter = population[survivors[a.min(b)]].genome();
+                        let other = population[survivors[a.max(b)]].genome();
+
+                        Genome::crossover(rng, fitter, other)
+                    } else {
+                        population[survivors[a]].genome().clone()
+                    };
+
+                child.mutate(rng, &mut self.history, &self.config);
+                new_population.push(I::from_genome(child));
+            }
+        }
+
+        for species in &mut self.species {
+            let representative = *species.members.choose(rng).unwrap();
+            species.representative = population[representative].genome().clone();
+        }
+
+        let stats = Statistics::from_fitness(population.iter().map(NeatIndividual::fitness));
+
+        (new_population, stats)
+    }
+
+    /// Assigns each genome to the first species whose representative is close
+    /// enough, creating new species as needed; species which end up empty are
+    /// dropped
+    fn speciate<I>(&mut self, population: &[I])
+    where
+        I: NeatIndividual,
+    {
+        for species in &mut self.species {
+            species.members.clear();
+        }
+
+        let config = &self.config;
+        let all_species = &mut self.species;
+
+        for (idx, individual) in population.iter().enumerate() {
+            let genome = individual.genome();
+
+            let species = all_species.iter_mut().find(|species| {
+                species.representative.distance(genome, config) < config.compatibility_threshold
+            });
+
+            match species {
+                Some(species) => species.members.push(idx),
+                None => all_species.push(Species {
+                    representative: genome.clone(),
+                    members: vec![idx],
+                }),
+            }
+        }
+
+        self.species.retain(|species| !species.members.is_empty());
+    }
+
+    /// Splits the next generation between species proportionally to their
+    /// shared fitness (each individual's fitness divided by the size of its
+    /// species), so that no single species can take over the population.
+    ///
+    /// Negative fitness is treated as zero.
+    fn offspring_counts<I>(&self, population: &[I]) -> Vec<usize>
+    where
+        I: NeatIndividual,
+    {
+        let shares: Vec<f32> = self
+            .species
+            .iter()
+            .map(|species| {
+                species
+                    .members
+                    .iter()
+                    .map(|&idx| population[idx].fitness().max(0.0))
+                    .sum::<f32>()
+                    / species.members.len() as f32
+            })
+            .collect();
+
+        let total: f32 = shares.iter().sum();
+
+        let shares: Vec<f32> = if total > 0.0 {
+            shares
+                .iter()
+                .map(|share| share / total * population.len() as f32)
+                .collect()
+        } else {
+            self.species
+                .iter()
+                .map(|species| species.members.len() as f32)
+                .collect()
+        };
+
+        let mut counts: Vec<usize> = shares.iter().map(|share| share.floor() as usize).collect();
+        let mut remaining = population.len() - counts.iter().sum::<usize>();
+
+        // Largest remainder method: whatever's left after rounding down goes
+        // to species which lost the most on it
+        let mut by_remainder: Vec<_> = (0..counts.len()).collect();
+
+        by_remainder.sort_by(|&a, &b| {
+            (shares[b] - shares[b].floor())
+                .partial_cmp(&(shares[a] - shares[a].floor()))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        for idx in by_remainder.into_iter().cycle() {
+            if remaining == 0 {
+                break;
+            }
+
+            counts[idx] += 1;
+            remaining -= 1;
+        }
+
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    struct TestIndividual {
+        fitness: f32,
+        genome: Genome,
+    }
+
+    impl NeatIndividual for TestIndividual {
+        fn fitness(&self) -> f32 {
+            self.fitness
+        }
+
+        fn genome(&self) -> &Genome {
+            &self.genome
+        }
+
+        fn from_genome(genome: Genome) -> Self {
+            // How close the network gets to computing `a AND NOT b`
+            let fitness = [
+                (0.0, 0.0, 0.0),
+                (0.0, 1.0, 0.0),
+                (1.0, 0.0, 1.0),
+                (1.0, 1.0, 0.0),
+            ]
+            .iter()
+            .map(|&(a, b, expected)| {
+                let actual = genome.propagate(&[a, b])[0];
+                1.0 - (expected - actual).abs().min(1.0)
+            })
+            .sum();
+
+            Self { fitness, genome }
+        }
+    }
+
+    #[test]
+    fn test_evolve() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut neat = Neat::new(2, 1, NeatConfig::default());
+
+        let mut population: Vec<_> = (0..50)
+            .map(|_| TestIndividual::from_genome(neat.random_genome(&mut rng)))
+            .collect();
+
+        let initial = Statistics::from_fitness(population.iter().map(|i| i.fitness));
+
+        for _ in 0..30 {
+            population = neat.evolve(&mut rng, &population).0;
+            assert_eq!(population.len(), 50);
+        }
+
+        let last = neat.evolve(&mut rng, &population).1;
+
+        assert!(last.max_fitness() > initial.max_fitness());
+        assert!(last.average_fitness() > initial.average_fitness());
+        assert!(!neat.species().is_empty());
+    }
+
+    #[test]
+    fn test_speciate() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut neat = Neat::new(
+            2,
+            1,
+            NeatConfig {
+                compatibility_threshold: 0.5,
+                ..NeatConfig::default()
+            },
+        );
+
+        let genome = neat.random_genome(&mut rng);
+        let mut grown = genome.clone();
+
+        for _ in 0..5 {
+            grown.add_node(&mut rng, &mut neat.history);
+        }
+
+        let population = vec![
+            TestIndividual::from_genome(genome.clone()),
+            TestIndividual::from_genome(grown.clone()),
+            TestIndividual::from_genome(genome),
+            TestIndividual::from_genome(grown),
+        ];
+
+        neat.speciate(&population);
+
+        let members: Vec<_> = neat
+            .species()
+            .iter()
+            .map(|species| species.members().to_vec())
+            .collect();
+
+        assert_eq!(members, vec![vec![0, 2], vec![1, 3]]);
+    }
+
+    #[test]
+    fn test_offspring_counts() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut neat = Neat::new(1, 1, NeatConfig::default());
+        let genome = neat.random_genome(&mut rng);
+
+        let individual = |fitness| TestIndividual {
+            fitness,
+            genome: genome.clone(),
+        };
+
+        let population = vec![
+            individual(1.0),
+            individual(1.0),
+            individual(1.0),
+            individual(4.0),
+            individual(-1.0),
+        ];
+
+        neat.species = vec![
+            Species {
+                representative: genome.clone(),
+                members: vec![0, 1, 2],
+            },
+            Species {
+                representative: genome.clone(),
+                members: vec![3, 4],
+            },
+        ];
+
+        // shared fitness: 1.0 vs 2.0
+        assert_eq!(neat.offspring_counts(&population), vec![2, 3]);
+
+        let population: Vec<_> = (0..5).map(|_| individual(0.0)).collect();
+
+        assert_eq!(neat.offspring_counts(&population), vec![3, 2]);
+    }
+}
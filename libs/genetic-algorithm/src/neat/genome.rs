@@ -0,0 +1,706 @@
+use std::collections::HashMap;
+
+use rand::{prelude::SliceRandom, Rng, RngCore};
+
+use crate::neat::{InnovationHistory, NeatConfig};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Input,
+    Hidden,
+    Output,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeGene {
+    pub id: usize,
+    pub kind: NodeKind,
+
+    /// Ignored for input nodes
+    pub bias: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionGene {
+    pub from: usize,
+    pub to: usize,
+    pub weight: f32,
+    pub enabled: bool,
+    pub innovation: usize,
+}
+
+/// Graph-based network genome, where both the weights and the shape of the
+/// network evolve.
+///
+/// Input nodes have ids `0..inputs`, output nodes `inputs..inputs + outputs`;
+/// hidden nodes get theirs from `InnovationHistory`. Connections never form
+/// cycles, so the network is always feed-forward; hidden and output nodes use
+/// `tanh()` as their activation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Genome {
+    inputs: usize,
+    outputs: usize,
+
+    /// Sorted by id
+    nodes: Vec<NodeGene>,
+
+    /// Sorted by innovation number
+    connections: Vec<ConnectionGene>,
+}
+
+impl Genome {
+    /// Creates a genome with every input connected to every output, using
+    /// random weights and biases
+    pub fn random(
+        rng: &mut dyn RngCore,
+        history: &mut InnovationHistory,
+        inputs: usize,
+        outputs: usize,
+    ) -> Self {
+        let nodes = (0..inputs)
+            .map(|id| NodeGene {
+                id,
+                kind: NodeKind::Input,
+                bias: 0.0,
+            })
+            .chain((inputs..inputs + outputs).map(|id| NodeGene {
+                id,
+                kind: NodeKind::Output,
+                bias: rng.gen_range(-1.0..=1.0),
+            }))
+            .collect();
+
+        let mut connections = Vec::with_capacity(inputs * outputs);
+
+        for from in 0..inputs {
+            for to in inputs..inputs + outputs {
+                connections.push(ConnectionGene {
+                    from,
+                    to,
+                    weight: rng.gen_range(-1.0..=1.0),
+                    enabled: true,
+                    innovation: history.connection(from, to),
+                });
+            }
+        }
+
+        connections.sort_by_key(|connection| connection.innovation);
+
+        Self {
+            inputs,
+            outputs,
+            nodes,
+            connections,
+        }
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    pub fn nodes(&self) -> &[NodeGene] {
+        &self.nodes
+    }
+
+    pub fn connections(&self) -> &[ConnectionGene] {
+        &self.connections
+    }
+
+    pub fn propagate(&self, inputs: &[f32]) -> Vec<f32> {
+        assert_eq!(
+            inputs.len(),
+            self.inputs,
+            "got {} inputs, but {} inputs were expected",
+            inputs.len(),
+            self.inputs
+        );
+
+        let mut values: HashMap<usize, f32> = HashMap::with_capacity(self.nodes.len());
+
+        for node in self.sorted_nodes() {
+            let value = if node.kind == NodeKind::Input {
+                inputs[node.id]
+            } else {
+                let sum: f32 = self
+                    .connections
+                    .iter()
+                    .filter(|connection| connection.enabled && connection.to == node.id)
+                    .map(|connection| values[&connection.from] * connection.weight)
+                    .sum();
+
+                (sum + node.bias).tanh()
+            };
+
+            values.insert(node.id, value);
+        }
+
+        (self.inputs..self.inputs + self.outputs)
+            .map(|id| values[&id])
+            .collect()
+    }
+
+    /// Nodes in topological order, i.e. each one after all of the nodes it
+    /// depends on
+    fn sorted_nodes(&self) -> Vec<&NodeGene> {
+        let mut pending: HashMap<usize, usize> =
+            self.nodes.iter().map(|node| (node.id, 0)).collect();
+
+        for connection in self.enabled_connections() {
+            *pending.get_mut(&connection.to).unwrap() += 1;
+        }
+
+        let mut ready: Vec<_> = self
+            .nodes
+            .iter()
+            .filter(|node| pending[&node.id] == 0)
+            .collect();
+
+        let mut sorted = Vec::with_capacity(self.nodes.len());
+
+        while let Some(node) = ready.pop() {
+            sorted.push(node);
+
+            for connection in self.enabled_connections() {
+                if connection.from == node.id {
+                    let pending = pending.get_mut(&connection.to).unwrap();
+                    *pending -= 1;
+
+                    if *pending == 0 {
+                        ready.push(self.node(connection.to).unwrap());
+                    }
+                }
+            }
+        }
+
+        sorted
+    }
+
+    fn enabled_connections(&self) -> impl Iterator<Item = &ConnectionGene> {
+        self.connections
+            .iter()
+            .filter(|connection| connection.enabled)
+    }
+
+    fn node(&self, id: usize) -> Option<&NodeGene> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|idx| &self.nodes[idx])
+    }
+
+    /// Tells whether `to` can be reached from `from`, following both enabled
+    /// and disabled connections (since the latter can get re-enabled later)
+    fn is_reachable(&self, from: usize, to: usize) -> bool {
+        let mut pending = vec![from];
+        let mut visited = vec![from];
+
+        while let Some(node) = pending.pop() {
+            if node == to {
+                return true;
+            }
+
+            for connection in &self.connections {
+                if connection.from == node && !visited.contains(&connection.to) {
+                    visited.push(connection.to);
+                    pending.push(connection.to);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Structural mutation: connects two so-far unconnected nodes, as long
+    /// as that doesn't create a cycle
+    pub fn add_connection(&mut self, rng: &mut dyn RngCore, history: &mut InnovationHistory) {
+        let mut candidates = Vec::new();
+
+        for from in &self.nodes {
+            for to in &self.nodes {
+                if from.kind == NodeKind::Output || to.kind == NodeKind::Input {
+                    continue;
+                }
+
+                let exists = self
+                    .connections
+                    .iter()
+                    .any(|connection| connection.from == from.id && connection.to == to.id);
+
+                if !exists && !self.is_reachable(to.id, from.id) {
+                    candidates.push((from.id, to.id));
+                }
+            }
+        }
+
+        let (from, to) = match candidates.choose(rng) {
+            Some(&candidate) => candidate,
+            None => return,
+        };
+
+        self.insert_connection(ConnectionGene {
+            from,
+            to,
+            weight: rng.gen_range(-1.0..=1.0),
+            enabled: true,
+            innovation: history.connection(from, to),
+        });
+    }
+
+    /// Structural mutation: splits an enabled connection in two, with a new
+    /// hidden node in between; the incoming connection gets weight of `1.0`,
+    /// the outgoing one keeps the old weight
+    pub fn add_node(&mut self, rng: &mut dyn RngCore, history: &mut InnovationHistory) {
+        let enabled: Vec<_> = (0..self.connections.len())
+            .filter(|&idx| self.connections[idx].enabled)
+            .collect();
+
+        let idx = match enabled.choose(rng) {
+            Some(&idx) => idx,
+            None => return,
+        };
+
+        let node = history.split(self.connections[idx].innovation);
+
+        // Happens when crossover re-enables a connection this genome has
+        // already split before
+        if self.node(node).is_some() {
+            return;
+        }
+
+        let connection = &mut self.connections[idx];
+        connection.enabled = false;
+
+        let (from, to, weight) = (connection.from, connection.to, connection.weight);
+
+        let node_idx = self.nodes.partition_point(|other| other.id < node);
+
+        self.nodes.insert(
+            node_idx,
+            NodeGene {
+                id: node,
+                kind: NodeKind::Hidden,
+                bias: 0.0,
+            },
+        );
+
+        self.insert_connection(ConnectionGene {
+            from,
+            to: node,
+            weight: 1.0,
+            enabled: true,
+            innovation: history.connection(from, node),
+        });
+
+        self.insert_connection(ConnectionGene {
+            from: node,
+            to,
+            weight,
+            enabled: true,
+            innovation: history.connection(node, to),
+        });
+    }
+
+    fn insert_connection(&mut self, connection: ConnectionGene) {
+        let idx = self
+            .connections
+            .partition_point(|other| other.innovation < connection.innovation);
+
+        self.connections.insert(idx, connection);
+    }
+
+    /// Non-structural mutation: nudges weights and biases
+    pub fn mutate_weights(&mut self, rng: &mut dyn RngCore, config: &NeatConfig) {
+        let genes = self
+            .connections
+            .iter_mut()
+            .map(|connection| &mut connection.weight)
+            .chain(
+                self.nodes
+                    .iter_mut()
+                    .filter(|node| node.kind != NodeKind::Input)
+                    .map(|node| &mut node.bias),
+            );
+
+        for gene in genes {
+            let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+
+            if rng.gen_bool(config.weight_mutation_chance as _) {
+                *gene += sign * config.weight_mutation_coefficient * rng.gen::<f32>();
+            }
+        }
+    }
+
+    /// Applies every kind of mutation, each with chance given in `config`
+    pub fn mutate(
+        &mut self,
+        rng: &mut dyn RngCore,
+        history: &mut InnovationHistory,
+        config: &NeatConfig,
+    ) {
+        self.mutate_weights(rng, config);
+
+        if rng.gen_bool(config.add_node_chance as _) {
+            self.add_node(rng, history);
+        }
+
+        if rng.gen_bool(config.add_connection_chance as _) {
+            self.add_connection(rng, history);
+        }
+    }
+
+    /// Lines up both genomes by innovation numbers; matching genes are taken
+    /// from a random parent, disjoint and excess ones from the fitter one.
+    ///
+    /// Gene disabled in either parent stays disabled with chance of 75%.
+    pub fn crossover(rng: &mut dyn RngCore, fitter: &Genome, other: &Genome) -> Genome {
+        let connections = fitter
+            .connections
+            .iter()
+            .map(|gene| {
+                let matching = other
+                    .connections
+                    .binary_search_by_key(&gene.innovation, |other| other.innovation)
+                    .ok()
+                    .map(|idx| &other.connections[idx]);
+
+                let mut child = match matching {
+                    Some(matching) if rng.gen_bool(0.5) => matching.clone(),
+                    _ => gene.clone(),
+                };
+
+                if let Some(matching) = matching {
+                    if !gene.enabled || !matching.enabled {
+                        child.enabled = !rng.gen_bool(0.75);
+                    }
+                }
+
+                child
+            })
+            .collect();
+
+        let nodes = fitter
+            .nodes
+            .iter()
+            .map(|node| match other.node(node.id) {
+                Some(matching) if rng.gen_bool(0.5) => matching.clone(),
+                _ => node.clone(),
+            })
+            .collect();
+
+        Genome {
+            inputs: fitter.inputs,
+            outputs: fitter.outputs,
+            nodes,
+            connections,
+        }
+    }
+
+    /// Compatibility distance, as defined by NEAT:
+    /// `c1 * excess / n + c2 * disjoint / n + c3 * average weight difference`,
+    /// where `n` is the number of genes in the larger genome
+    pub fn distance(&self, other: &Genome, config: &NeatConfig) -> f32 {
+        let (mut a, mut b) = (
+            self.connections.iter().peekable(),
+            other.connections.iter().peekable(),
+        );
+        let (mut matching, mut disjoint, mut excess) = (0, 0, 0);
+        let mut weight_difference = 0.0;
+
+        loop {
+            match (a.peek(), b.peek()) {
+                (Some(gene_a), Some(gene_b)) => {
+                    if gene_a.innovation == gene_b.innovation {
+                        matching += 1;
+                        weight_difference += (gene_a.weight - gene_b.weight).abs();
+                        a.next();
+                        b.next();
+                    } else if gene_a.innovation < gene_b.innovation {
+                        disjoint += 1;
+                        a.next();
+                    } else {
+                        disjoint += 1;
+                        b.next();
+                    }
+                }
+
+                (Some(_), None) => {
+                    excess += 1;
+                    a.next();
+                }
+
+                (None, Some(_)) => {
+                    excess += 1;
+                    b.next();
+                }
+
+                (None, None) => break,
+            }
+        }
+
+        let n = self.connections.len().max(other.connections.len()).max(1) as f32;
+
+        let weight_difference = if matching > 0 {
+            weight_difference / matching as f32
+        } else {
+            0.0
+        };
+
+        config.excess_coefficient * excess as f32 / n
+            + config.disjoint_coefficient * disjoint as f32 / n
+            + config.weight_coefficient * weight_difference
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn connection(from: usize, to: usize, weight: f32, innovation: usize) -> ConnectionGene {
+        ConnectionGene {
+            from,
+            to,
+            weight,
+            enabled: true,
+            innovation,
+        }
+    }
+
+    fn node(id: usize, kind: NodeKind) -> NodeGene {
+        NodeGene {
+            id,
+            kind,
+            bias: 0.0,
+        }
+    }
+
+    /// 2 inputs (0, 1), 1 output (2) and 1 hidden node (3) between input 0
+    /// and the output
+    fn genome() -> Genome {
+        Genome {
+            inputs: 2,
+            outputs: 1,
+            nodes: vec![
+                node(0, NodeKind::Input),
+                node(1, NodeKind::Input),
+                node(2, NodeKind::Output),
+                node(3, NodeKind::Hidden),
+            ],
+            connections: vec![
+                ConnectionGene {
+                    enabled: false,
+                    ..connection(0, 2, 0.5, 0)
+                },
+                connection(1, 2, -1.0, 1),
+                connection(0, 3, 1.0, 2),
+                connection(3, 2, 0.5, 3),
+            ],
+        }
+    }
+
+    fn history() -> InnovationHistory {
+        let mut history = InnovationHistory::new(4);
+
+        for connection in genome().connections() {
+            history.connection(connection.from, connection.to);
+        }
+
+        history
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut history = InnovationHistory::new(5);
+        let genome = Genome::random(&mut rng, &mut history, 3, 2);
+
+        assert_eq!(genome.nodes().len(), 5);
+
+        let actual: Vec<_> = genome
+            .connections()
+            .iter()
+            .map(|connection| (connection.from, connection.to, connection.innovation))
+            .collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                (0, 3, 0),
+                (0, 4, 1),
+                (1, 3, 2),
+                (1, 4, 3),
+                (2, 3, 4),
+                (2, 4, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_propagate() {
+        let actual = genome().propagate(&[0.5, 0.25]);
+        let hidden = (0.5f32 * 1.0).tanh();
+        let expected = (hidden * 0.5 - 0.25).tanh();
+
+        approx::assert_relative_eq!(actual.as_slice(), [expected].as_ref());
+    }
+
+    #[test]
+    fn test_add_node() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut history = history();
+        let mut genome = genome();
+
+        genome.add_node(&mut rng, &mut history);
+
+        assert_eq!(genome.nodes().len(), 5);
+        assert_eq!(genome.nodes()[4], node(4, NodeKind::Hidden));
+        assert_eq!(genome.connections().len(), 6);
+
+        let split: Vec<_> = genome.connections()[1..4]
+            .iter()
+            .filter(|connection| !connection.enabled)
+            .collect();
+
+        assert_eq!(split.len(), 1);
+
+        let split = split[0];
+
+        assert_eq!(genome.connections()[4], connection(split.from, 4, 1.0, 4));
+
+        assert_eq!(
+            genome.connections()[5],
+            connection(4, split.to, split.weight, 5)
+        );
+    }
+
+    #[test]
+    fn test_add_connection() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut history = history();
+        let mut genome = genome();
+
+        genome.add_connection(&mut rng, &mut history);
+
+        assert_eq!(genome.connections().len(), 5);
+
+        // That's the only pair of nodes which can be connected without
+        // creating a cycle
+        let added = &genome.connections()[4];
+
+        assert_eq!((added.from, added.to, added.innovation), (1, 3, 4));
+    }
+
+    #[test]
+    fn test_add_connection_never_creates_cycles() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut history = InnovationHistory::new(4);
+        let mut genome = Genome::random(&mut rng, &mut history, 2, 2);
+
+        for _ in 0..50 {
+            genome.add_node(&mut rng, &mut history);
+            genome.add_connection(&mut rng, &mut history);
+        }
+
+        assert_eq!(genome.sorted_nodes().len(), genome.nodes().len());
+
+        for connection in genome.connections() {
+            assert!(!genome.is_reachable(connection.to, connection.from));
+        }
+    }
+
+    #[test]
+    fn test_same_mutation_gets_same_innovation() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut history = InnovationHistory::new(3);
+        let mut a = Genome::random(&mut rng, &mut history, 2, 1);
+        let mut b = Genome::random(&mut rng, &mut history, 2, 1);
+
+        // Same seed makes both genomes split the same connection
+        a.add_node(&mut ChaCha8Rng::from_seed([1; 32]), &mut history);
+        b.add_node(&mut ChaCha8Rng::from_seed([1; 32]), &mut history);
+
+        let structure = |genome: &Genome| -> Vec<_> {
+            genome
+                .connections()
+                .iter()
+                .map(|connection| {
+                    (
+                        connection.from,
+                        connection.to,
+                        connection.enabled,
+                        connection.innovation,
+                    )
+                })
+                .collect()
+        };
+
+        assert_eq!(structure(&a), structure(&b));
+        assert_eq!(a.nodes().len(), 4);
+        assert_eq!(a.nodes()[3].id, b.nodes()[3].id);
+    }
+
+    #[test]
+    fn test_crossover() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let fitter = genome();
+
+        let other = Genome {
+            connections: vec![connection(0, 2, -0.5, 0), connection(1, 2, 2.0, 1)],
+            nodes: fitter.nodes[..3].to_vec(),
+            ..fitter.clone()
+        };
+
+        let child = Genome::crossover(&mut rng, &fitter, &other);
+
+        let innovations: Vec<_> = child
+            .connections()
+            .iter()
+            .map(|connection| connection.innovation)
+            .collect();
+
+        assert_eq!(innovations, vec![0, 1, 2, 3]);
+        assert_eq!(child.nodes(), fitter.nodes());
+        assert_eq!(child.connections()[2..], fitter.connections()[2..]);
+
+        let child = Genome::crossover(&mut rng, &other, &fitter);
+
+        assert_eq!(child.connections().len(), 2);
+        assert_eq!(child.nodes(), other.nodes());
+    }
+
+    #[test]
+    fn test_distance() {
+        let config = NeatConfig {
+            excess_coefficient: 1.0,
+            disjoint_coefficient: 2.0,
+            weight_coefficient: 0.5,
+            ..NeatConfig::default()
+        };
+
+        let a = genome();
+
+        let b = Genome {
+            connections: vec![
+                connection(0, 2, 1.5, 0),
+                connection(1, 2, -1.0, 1),
+                connection(0, 3, 1.0, 4),
+            ],
+            ..genome()
+        };
+
+        // matching: 0 (difference: 1.0) and 1 (difference: 0.0),
+        // disjoint: 2 and 3, excess: 4
+        let expected = 1.0 * 1.0 / 4.0 + 2.0 * 2.0 / 4.0 + 0.5 * 0.5;
+
+        approx::assert_relative_eq!(a.distance(&b, &config), expected);
+        approx::assert_relative_eq!(b.distance(&a, &config), expected);
+        approx::assert_relative_eq!(a.distance(&a, &config), 0.0);
+    }
+}
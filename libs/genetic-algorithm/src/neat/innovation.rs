@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+/// Hands out innovation numbers and hidden node ids, making sure that the same
+/// structural mutation gets the same numbers no matter in which genome (or
+/// generation) it happens - that's what allows to line up genes of two
+/// different genomes during crossover.
+#[derive(Clone, Debug)]
+pub struct InnovationHistory {
+    next_innovation: usize,
+    next_node: usize,
+    connections: HashMap<(usize, usize), usize>,
+    splits: HashMap<usize, usize>,
+}
+
+impl InnovationHistory {
+    /// `first_hidden_node` is the first id not taken by any input nor output
+    /// node
+    pub fn new(first_hidden_node: usize) -> Self {
+        Self {
+            next_innovation: 0,
+            next_node: first_hidden_node,
+            connections: HashMap::new(),
+            splits: HashMap::new(),
+        }
+    }
+
+    /// Returns innovation number of connection going `from` -> `to`
+    pub fn connection(&mut self, from: usize, to: usize) -> usize {
+        let next_innovation = &mut self.next_innovation;
+
+        *self.connections.entry((from, to)).or_insert_with(|| {
+            *next_innovation += 1;
+            *next_innovation - 1
+        })
+    }
+
+    /// Returns id of the node created by splitting connection with given
+    /// innovation number
+    pub fn split(&mut self, innovation: usize) -> usize {
+        let next_node = &mut self.next_node;
+
+        *self.splits.entry(innovation).or_insert_with(|| {
+            *next_node += 1;
+            *next_node - 1
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection() {
+        let mut history = InnovationHistory::new(3);
+
+        assert_eq!(history.connection(0, 2), 0);
+        assert_eq!(history.connection(1, 2), 1);
+        assert_eq!(history.connection(0, 2), 0);
+        assert_eq!(history.connection(2, 0), 2);
+    }
+
+    #[test]
+    fn test_split() {
+        let mut history = InnovationHistory::new(3);
+
+        assert_eq!(history.split(0), 3);
+        assert_eq!(history.split(5), 4);
+        assert_eq!(history.split(0), 3);
+    }
+}
@@ -0,0 +1,103 @@
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, Normal};
+
+use crate::{chromosome::Chromosome, MutationContext, MutationMethod};
+
+/// Adds noise from a normal distribution with mean zero and standard
+/// deviation `sigma` to touched genes; unlike `GaussianMutation`, which is
+/// uniform despite its name, small changes are much more likely than large
+/// ones, yet no change is ruled out.
+#[derive(Clone, Debug)]
+pub struct NormalMutation {
+    /// probability of mutating each gene
+    chance: f32,
+
+    /// Standard deviation of the noise
+    sigma: f32,
+}
+
+impl NormalMutation {
+    pub fn new(chance: f32, sigma: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(sigma >= 0.0);
+
+        Self { chance, sigma }
+    }
+}
+
+impl MutationMethod for NormalMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome, _: &MutationContext) {
+        let noise = Normal::new(0.0, self.sigma).unwrap();
+
+        child.iter_mut().for_each(|gene| {
+            if rng.gen_bool(self.chance as _) {
+                *gene += noise.sample(rng);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn perturbations(chance: f32, sigma: f32) -> Vec<f32> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child: Chromosome = vec![1.0; 10_000].into_iter().collect();
+
+        NormalMutation::new(chance, sigma).mutate(&mut rng, &mut child, &Default::default());
+
+        child.iter().map(|gene| gene - 1.0).collect()
+    }
+
+    fn fraction(values: &[f32], predicate: impl Fn(f32) -> bool) -> f32 {
+        values.iter().filter(|&&value| predicate(value)).count() as f32 / values.len() as f32
+    }
+
+    #[test]
+    fn test_distribution() {
+        let perturbations = perturbations(1.0, 0.5);
+
+        let mean = perturbations.iter().sum::<f32>() / perturbations.len() as f32;
+        let std_dev = (perturbations
+            .iter()
+            .map(|p| (p - mean).powi(2))
+            .sum::<f32>()
+            / perturbations.len() as f32)
+            .sqrt();
+
+        approx::assert_relative_eq!(mean, 0.0, epsilon = 0.02);
+        approx::assert_relative_eq!(std_dev, 0.5, epsilon = 0.0125);
+
+        // The 68-95-99.7 rule
+        approx::assert_relative_eq!(
+            fraction(&perturbations, |p| p.abs() < 0.5),
+            0.683,
+            epsilon = 0.015
+        );
+        approx::assert_relative_eq!(
+            fraction(&perturbations, |p| p.abs() < 1.0),
+            0.954,
+            epsilon = 0.01
+        );
+        approx::assert_relative_eq!(
+            fraction(&perturbations, |p| p.abs() < 1.5),
+            0.997,
+            epsilon = 0.003
+        );
+    }
+
+    #[test]
+    fn test_chance() {
+        let actual = perturbations(0.25, 0.5);
+
+        approx::assert_relative_eq!(
+            fraction(&actual, |p| p != 0.0),
+            0.25,
+            epsilon = 0.015
+        );
+        approx::assert_relative_eq!(fraction(&perturbations(0.0, 0.5), |p| p != 0.0), 0.0);
+    }
+}
@@ -0,0 +1,123 @@
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, Normal};
+
+use crate::{chromosome::Chromosome, MutationContext, MutationMethod};
+
+/// Mutation following Rechenberg's 1/5th success rule: touched genes get
+/// noise from a normal distribution whose standard deviation grows (is
+/// divided by `factor`) whenever more than a fifth of the previous
+/// generation's children have beaten their parents, and shrinks (is
+/// multiplied by `factor`) whenever fewer did.
+///
+/// The success rate comes from `MutationContext`; standard deviation is
+/// adjusted by `adapt()`, which `GeneticAlgorithm` and `SteadyState` call
+/// once per generation.
+#[derive(Clone, Debug)]
+pub struct OneFifthRuleMutation {
+    /// probability of mutating each gene
+    chance: f32,
+
+    /// How much the standard deviation changes per generation
+    factor: f32,
+
+    sigma: f32,
+}
+
+impl OneFifthRuleMutation {
+    pub fn new(chance: f32, sigma: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(sigma > 0.0);
+
+        Self {
+            chance,
+            factor: 0.85,
+            sigma,
+        }
+    }
+
+    /// Defaults to `0.85`
+    pub fn with_factor(mut self, factor: f32) -> Self {
+        assert!(factor > 0.0 && factor < 1.0);
+
+        self.factor = factor;
+        self
+    }
+
+    /// Current standard deviation
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+}
+
+impl MutationMethod for OneFifthRuleMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome, _: &MutationContext) {
+        let noise = Normal::new(0.0, self.sigma).unwrap();
+
+        child.iter_mut().for_each(|gene| {
+            if rng.gen_bool(self.chance as _) {
+                *gene += noise.sample(rng);
+            }
+        })
+    }
+
+    fn adapt(&mut self, context: &MutationContext) {
+        if let Some(success_rate) = context.success_rate {
+            if success_rate > 0.2 {
+                self.sigma /= self.factor;
+            } else if success_rate < 0.2 {
+                self.sigma *= self.factor;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn sigma_after(success_rates: &[Option<f32>]) -> f32 {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut method = OneFifthRuleMutation::new(1.0, 1.0).with_factor(0.5);
+        let mut child: Chromosome = vec![0.0; 3].into_iter().collect();
+
+        for (generation, &success_rate) in success_rates.iter().enumerate() {
+            let context = MutationContext {
+                generation,
+                success_rate,
+            };
+
+            method.adapt(&context);
+
+            // Mutating doesn't adapt on its own
+            method.mutate(&mut rng, &mut child, &context);
+            method.mutate(&mut rng, &mut child, &context);
+        }
+
+        method.sigma()
+    }
+
+    #[test]
+    fn test() {
+        approx::assert_relative_eq!(sigma_after(&[None]), 1.0);
+        approx::assert_relative_eq!(sigma_after(&[None, Some(0.5)]), 2.0);
+        approx::assert_relative_eq!(sigma_after(&[None, Some(0.2)]), 1.0);
+        approx::assert_relative_eq!(sigma_after(&[None, Some(0.1), Some(0.0)]), 0.25);
+        approx::assert_relative_eq!(sigma_after(&[Some(0.0), Some(0.9), Some(0.0)]), 0.5);
+    }
+
+    #[test]
+    fn test_spread() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let method = OneFifthRuleMutation::new(1.0, 0.5);
+        let mut child: Chromosome = vec![0.0; 10_000].into_iter().collect();
+
+        method.mutate(&mut rng, &mut child, &MutationContext::default());
+
+        let std_dev =
+            (child.iter().map(|gene| gene.powi(2)).sum::<f32>() / child.len() as f32).sqrt();
+
+        approx::assert_relative_eq!(std_dev, 0.5, epsilon = 0.0125);
+    }
+}
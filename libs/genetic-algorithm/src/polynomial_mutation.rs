@@ -0,0 +1,127 @@
+use rand::{Rng, RngCore};
+
+use crate::{chromosome::Chromosome, MutationContext, MutationMethod};
+
+/// Deb's polynomial mutation for genes bounded to `min..=max`: touched genes
+/// move by an amount drawn from a polynomial distribution that never takes
+/// them out of bounds, and concentrates around the original value the more,
+/// the larger the distribution index `eta` is (20 is a common choice).
+#[derive(Clone, Debug)]
+pub struct PolynomialMutation {
+    /// probability of mutating each gene
+    chance: f32,
+
+    /// Distribution index
+    eta: f32,
+
+    min: f32,
+    max: f32,
+}
+
+impl PolynomialMutation {
+    pub fn new(chance: f32, eta: f32, min: f32, max: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(eta >= 0.0);
+        assert!(min < max);
+
+        Self {
+            chance,
+            eta,
+            min,
+            max,
+        }
+    }
+
+    fn perturb(&self, rng: &mut dyn RngCore, gene: f32) -> f32 {
+        let gene = gene.clamp(self.min, self.max);
+        let range = self.max - self.min;
+        let exponent = 1.0 / (self.eta + 1.0);
+        let u: f32 = rng.gen_range(0.0..1.0);
+
+        let delta = if u < 0.5 {
+            let distance = (gene - self.min) / range;
+            let value = 2.0 * u + (1.0 - 2.0 * u) * (1.0 - distance).powf(self.eta + 1.0);
+
+            value.powf(exponent) - 1.0
+        } else {
+            let distance = (self.max - gene) / range;
+            let value = 2.0 * (1.0 - u) + 2.0 * (u - 0.5) * (1.0 - distance).powf(self.eta + 1.0);
+
+            1.0 - value.powf(exponent)
+        };
+
+        (gene + delta * range).clamp(self.min, self.max)
+    }
+}
+
+impl MutationMethod for PolynomialMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome, _: &MutationContext) {
+        child.iter_mut().for_each(|gene| {
+            if rng.gen_bool(self.chance as _) {
+                *gene = self.perturb(rng, *gene);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn mutated(eta: f32, gene: f32) -> Vec<f32> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child: Chromosome = vec![gene; 10_000].into_iter().collect();
+
+        PolynomialMutation::new(1.0, eta, -1.0, 1.0).mutate(
+            &mut rng,
+            &mut child,
+            &Default::default(),
+        );
+
+        child.iter().copied().collect()
+    }
+
+    fn mean_distance(genes: &[f32], from: f32) -> f32 {
+        genes.iter().map(|gene| (gene - from).abs()).sum::<f32>() / genes.len() as f32
+    }
+
+    #[test]
+    fn test_distribution() {
+        let genes = mutated(20.0, 0.0);
+
+        let mean = genes.iter().sum::<f32>() / genes.len() as f32;
+        let below = genes.iter().filter(|&&gene| gene < 0.0).count() as f32 / genes.len() as f32;
+
+        approx::assert_relative_eq!(mean, 0.0, epsilon = 0.005);
+        approx::assert_relative_eq!(below, 0.5, epsilon = 0.015);
+
+        // Away from bounds, |delta| = 1 - u^(1 / (eta + 1)) for uniform u, whose
+        // mean is 1 / (eta + 2); perturbation is twice that, as range is 2.0
+        approx::assert_relative_eq!(mean_distance(&genes, 0.0), 2.0 / 22.0, epsilon = 0.005);
+    }
+
+    #[test]
+    fn test_eta() {
+        let wide = mean_distance(&mutated(1.0, 0.0), 0.0);
+        let narrow = mean_distance(&mutated(100.0, 0.0), 0.0);
+
+        assert!(wide > 5.0 * narrow);
+    }
+
+    #[test]
+    fn test_bounds() {
+        for &gene in &[-1.0, -0.99, 0.5, 1.0] {
+            let genes = mutated(0.0, gene);
+
+            assert!(genes.iter().all(|gene| (-1.0..=1.0).contains(gene)));
+            assert!(genes.iter().any(|&other| other != gene));
+        }
+
+        // Out of bounds genes get clamped first
+        assert!(mutated(20.0, 5.0)
+            .iter()
+            .all(|&gene| gene > 0.0 && gene <= 1.0));
+    }
+}
@@ -0,0 +1,224 @@
+use std::cmp::Ordering;
+
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    RngCore,
+};
+
+use crate::Individual;
+use crate::SelectionMethod;
+
+/// How `RankSelection` turns ranks into selection weights; ranks go from `0`
+/// for the least fit individual to `n - 1` for the fittest one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ranking {
+    /// Weights grow linearly from `2 - pressure` for the least fit individual
+    /// up to `pressure` for the fittest one; `pressure` must be within
+    /// `1.0..=2.0`, where `1.0` means uniformly random selection and `2.0`
+    /// means the least fit individual never gets picked.
+    Linear { pressure: f32 },
+
+    /// Each individual weighs `base` times as much as the next fitter one;
+    /// `base` must be within `0.0..1.0` (exclusive) and the smaller it is,
+    /// the more selection favours the top of the population.
+    Exponential { base: f32 },
+}
+
+/// Like `RouletteWheelSelection`, but with weights based on the order of
+/// fitness instead of fitness itself - so a single outstanding individual
+/// doesn't take over the population, and zero or negative fitness is fine.
+#[derive(Clone, Debug)]
+pub struct RankSelection {
+    ranking: Ranking,
+}
+
+impl RankSelection {
+    pub fn new(ranking: Ranking) -> Self {
+        match ranking {
+            Ranking::Linear { pressure } => {
+                assert!((1.0..=2.0).contains(&pressure));
+            }
+
+            Ranking::Exponential { base } => {
+                assert!(base > 0.0 && base < 1.0);
+            }
+        }
+
+        Self { ranking }
+    }
+
+    pub fn linear(pressure: f32) -> Self {
+        Self::new(Ranking::Linear { pressure })
+    }
+
+    pub fn exponential(base: f32) -> Self {
+        Self::new(Ranking::Exponential { base })
+    }
+
+    pub fn ranking(&self) -> Ranking {
+        self.ranking
+    }
+
+    fn weight(&self, rank: usize, len: usize) -> f32 {
+        if len == 1 {
+            return 1.0;
+        }
+
+        match self.ranking {
+            Ranking::Linear { pressure } => {
+                (2.0 - pressure) + 2.0 * (pressure - 1.0) * rank as f32 / (len - 1) as f32
+            }
+
+            Ranking::Exponential { base } => base.powi((len - 1 - rank) as _),
+        }
+    }
+
+    /// Returns population sorted from the least fit individual, together
+    /// with a distribution over its indices
+    fn ranked<'a, I>(&self, population: &'a [I]) -> (Vec<&'a I>, WeightedIndex<f32>)
+    where
+        I: Individual,
+    {
+        assert!(
+            !population.is_empty(),
+            "got empty population, not going to work"
+        );
+
+        let mut ranked: Vec<&I> = population.iter().collect();
+
+        ranked.sort_by(|a, b| {
+            a.fitness()
+                .partial_cmp(&b.fitness())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let weights = (0..ranked.len()).map(|rank| self.weight(rank, ranked.len()));
+        let distribution = WeightedIndex::new(weights).expect("invalid rank weights");
+
+        (ranked, distribution)
+    }
+}
+
+impl SelectionMethod for RankSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        let (ranked, distribution) = self.ranked(population);
+
+        ranked[distribution.sample(rng)]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        let (ranked, distribution) = self.ranked(population);
+
+        (0..count)
+            .map(|_| ranked[distribution.sample(rng)])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, iter::FromIterator};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::tests::TestIndividual;
+
+    fn histogram(method: RankSelection, population: &[TestIndividual]) -> BTreeMap<i32, usize> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        method
+            .select_many(&mut rng, population, 1_000)
+            .into_iter()
+            .fold(Default::default(), |mut histogram, individual| {
+                *histogram.entry(individual.fitness() as _).or_default() += 1;
+                histogram
+            })
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(40.0),
+            TestIndividual::new(-3.0),
+        ]
+    }
+
+    #[test]
+    fn test_linear() {
+        // Weights: 1/2, 5/6, 7/6, 3/2 - fitness of 40 doesn't matter more
+        // than fitness of 3 would
+        let actual_histogram = histogram(RankSelection::linear(1.5), &population());
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // fitness, count
+            (-3, 134),
+            (1, 193),
+            (2, 310),
+            (40, 363),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn test_linear_uniform() {
+        let actual_histogram = histogram(RankSelection::linear(1.0), &population());
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // fitness, count
+            (-3, 248),
+            (1, 253),
+            (2, 270),
+            (40, 229),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn test_linear_max_pressure() {
+        let actual_histogram = histogram(RankSelection::linear(2.0), &population());
+
+        assert_eq!(actual_histogram.get(&-3), None);
+    }
+
+    #[test]
+    fn test_exponential() {
+        // Weights: 1/8, 1/4, 1/2, 1
+        let actual_histogram = histogram(RankSelection::exponential(0.5), &population());
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // fitness, count
+            (-3, 72),
+            (1, 130),
+            (2, 273),
+            (40, 525),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn test_select() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let population = vec![TestIndividual::new(5.0)];
+
+        let actual = RankSelection::exponential(0.1).select(&mut rng, &population);
+
+        assert_eq!(actual, &population[0]);
+    }
+}
@@ -0,0 +1,42 @@
+use std::cmp::Ordering;
+
+/// How `GeneticAlgorithm::evolve()` builds the next generation out of the
+/// current one and the λ children it has bred.
+///
+/// Whatever the strategy, the top `elitism` individuals of the current
+/// generation always get carried over first.
+///
+/// Children's fitness is known only once the caller has evaluated them (e.g.
+/// `lib-simulation` has to let animals live first), so `Plus` and `Comma`
+/// pick survivors from the population `evolve()` gets - i.e. from the
+/// survivors and children returned by the previous call, all evaluated by
+/// now - and return them together with the new children. Population they
+/// work on stays at μ + λ individuals, with μ being the size of the
+/// population `evolve()` has been called with for the first time. Parents
+/// of the new children are selected from the whole population `evolve()`
+/// gets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Replacement {
+    /// Children replace the current generation - the next one consists of
+    /// the elites and all λ children, whatever their fitness
+    #[default]
+    Generational,
+
+    /// (μ+λ): μ fittest individuals survive, whether they are children or
+    /// not
+    Plus,
+
+    /// (μ,λ): besides the elites, only children (of the previous call) get
+    /// to survive, so that μ survive in total; requires at least
+    /// `μ - elitism` children
+    Comma,
+}
+
+/// Sorts individuals from the fittest one
+pub(crate) fn rank<I>(individuals: &mut [I], fitness: impl Fn(&I) -> f32) {
+    individuals.sort_by(|a, b| {
+        fitness(b)
+            .partial_cmp(&fitness(a))
+            .unwrap_or(Ordering::Equal)
+    });
+}
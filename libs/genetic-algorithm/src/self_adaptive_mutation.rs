@@ -0,0 +1,150 @@
+use rand::RngCore;
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::{chromosome::Chromosome, MutationContext, MutationMethod};
+
+/// Self-adaptive mutation, as in evolution strategies: the chromosome
+/// carries its own step sizes, which get evolved together with the genes.
+///
+/// Chromosomes are expected to consist of `n` object genes (the actual
+/// solution) followed by `n` strategy genes (standard deviations, one per
+/// object gene) - see `add_strategy_genes()` and `object_genes()`. Each
+/// `mutate()` first perturbs the step sizes log-normally, then adds noise of
+/// the new size to every object gene.
+#[derive(Clone, Debug)]
+pub struct SelfAdaptiveMutation {
+    /// Step sizes never go below this value, so that mutation doesn't die out
+    min_sigma: f32,
+}
+
+impl SelfAdaptiveMutation {
+    pub fn new() -> Self {
+        Self { min_sigma: 1e-4 }
+    }
+
+    /// Defaults to `1e-4`
+    pub fn with_min_sigma(mut self, min_sigma: f32) -> Self {
+        assert!(min_sigma >= 0.0);
+
+        self.min_sigma = min_sigma;
+        self
+    }
+
+    /// Appends a strategy gene of `sigma` for each gene of `chromosome`
+    pub fn add_strategy_genes(chromosome: &Chromosome, sigma: f32) -> Chromosome {
+        chromosome
+            .iter()
+            .copied()
+            .chain(chromosome.iter().map(|_| sigma))
+            .collect()
+    }
+
+    /// Returns the chromosome without its strategy genes
+    pub fn object_genes(chromosome: &Chromosome) -> Chromosome {
+        chromosome
+            .iter()
+            .take(chromosome.len() / 2)
+            .copied()
+            .collect()
+    }
+}
+
+impl Default for SelfAdaptiveMutation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MutationMethod for SelfAdaptiveMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome, _: &MutationContext) {
+        assert!(
+            child.len() % 2 == 0,
+            "Chromosome has to have a strategy gene for each object gene"
+        );
+
+        let n = child.len() / 2;
+
+        if n == 0 {
+            return;
+        }
+
+        // Usual learning rates for the shared and the per-gene perturbation
+        let tau_global = 1.0 / (2.0 * n as f32).sqrt();
+        let tau_local = 1.0 / (2.0 * (n as f32).sqrt()).sqrt();
+
+        let global: f32 = tau_global * standard_normal(rng);
+        let mut genes: Vec<&mut f32> = child.iter_mut().collect();
+        let (object, strategy) = genes.split_at_mut(n);
+
+        for (gene, sigma) in object.iter_mut().zip(strategy.iter_mut()) {
+            let local: f32 = tau_local * standard_normal(rng);
+            let noise = standard_normal(rng);
+
+            **sigma = (**sigma * (global + local).exp()).max(self.min_sigma);
+            **gene += **sigma * noise;
+        }
+    }
+}
+
+fn standard_normal(rng: &mut dyn RngCore) -> f32 {
+    StandardNormal.sample(rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_strategy_genes() {
+        let chromosome: Chromosome = vec![1.0, 2.0, 3.0].into_iter().collect();
+        let extended = SelfAdaptiveMutation::add_strategy_genes(&chromosome, 0.5);
+
+        let expected: Chromosome = vec![1.0, 2.0, 3.0, 0.5, 0.5, 0.5].into_iter().collect();
+
+        assert_eq!(extended, expected);
+        assert_eq!(SelfAdaptiveMutation::object_genes(&extended), chromosome);
+    }
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child: Chromosome = vec![1.0, 2.0, 0.5, 0.5].into_iter().collect();
+
+        SelfAdaptiveMutation::new().mutate(&mut rng, &mut child, &MutationContext::default());
+
+        let expected: Chromosome = vec![-0.51577914, 1.7883282, 1.267089, 0.315094]
+            .into_iter()
+            .collect();
+
+        assert_eq!(child, expected);
+    }
+
+    #[test]
+    fn test_step_sizes() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let method = SelfAdaptiveMutation::new().with_min_sigma(0.01);
+
+        // Genes with tiny step sizes barely move, genes with large ones move a
+        // lot - whatever happens to the step sizes themselves
+        let zeros: Chromosome = vec![0.0; 100].into_iter().collect();
+        let mut small = SelfAdaptiveMutation::add_strategy_genes(&zeros, 0.01);
+        let mut large = SelfAdaptiveMutation::add_strategy_genes(&zeros, 10.0);
+
+        method.mutate(&mut rng, &mut small, &MutationContext::default());
+        method.mutate(&mut rng, &mut large, &MutationContext::default());
+
+        let spread = |chromosome: &Chromosome| {
+            SelfAdaptiveMutation::object_genes(chromosome)
+                .iter()
+                .map(|gene| gene.abs())
+                .sum::<f32>()
+                / 100.0
+        };
+
+        assert!(spread(&small) < 0.05);
+        assert!(spread(&large) > 2.0);
+        assert!(small.iter().skip(100).all(|&sigma| sigma >= 0.01));
+    }
+}
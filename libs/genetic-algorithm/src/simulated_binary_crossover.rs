@@ -0,0 +1,121 @@
+use rand::{Rng, RngCore};
+
+use crate::{chromosome::Chromosome, CrossoverMethod};
+
+/// SBX: mimics single-point crossover of binary-encoded genes by placing
+/// the child at `mean ± beta * (b - a) / 2`, where `mean` is the parents'
+/// midpoint and `beta`'s distribution depends on the distribution index
+/// `eta`.
+///
+/// The larger `eta`, the closer children stay to their parents; 2 to 5 are
+/// common choices for exploration, 10 to 20 for fine-tuning.
+#[derive(Clone, Debug)]
+pub struct SimulatedBinaryCrossover {
+    eta: f32,
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(eta: f32) -> Self {
+        assert!(eta >= 0.0);
+
+        Self { eta }
+    }
+
+    fn beta(&self, rng: &mut dyn RngCore) -> f32 {
+        let u: f32 = rng.gen_range(0.0..1.0);
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        if u <= 0.5 {
+            (2.0 * u).powf(exponent)
+        } else {
+            (1.0 / (2.0 * (1.0 - u))).powf(exponent)
+        }
+    }
+}
+
+impl CrossoverMethod for SimulatedBinaryCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(
+            parent_a.len(),
+            parent_b.len(),
+            "Both parents have to be in the same length"
+        );
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let beta = self.beta(rng);
+                let offset = beta * (b - a) / 2.0;
+
+                // SBX creates two siblings, symmetric around the midpoint;
+                // we pick one of them at random
+                if rng.gen_bool(0.5) {
+                    (a + b) / 2.0 - offset
+                } else {
+                    (a + b) / 2.0 + offset
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let parent_a: Chromosome = vec![1.0, 2.0, -3.0, 4.0].into_iter().collect();
+        let parent_b: Chromosome = vec![3.0, 2.0, 1.0, -4.0].into_iter().collect();
+
+        let child = SimulatedBinaryCrossover::new(2.0).crossover(&mut rng, &parent_a, &parent_b);
+        let expected: Chromosome = vec![2.720792, 2.0, -3.0485077, 3.1983404]
+            .into_iter()
+            .collect();
+
+        assert_eq!(child, expected);
+    }
+
+    fn child(eta: f32) -> Chromosome {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let parent_a: Chromosome = vec![-1.0; 10_000].into_iter().collect();
+        let parent_b: Chromosome = vec![1.0; 10_000].into_iter().collect();
+
+        SimulatedBinaryCrossover::new(eta).crossover(&mut rng, &parent_a, &parent_b)
+    }
+
+    /// Mean absolute distance of genes from the parents' midpoint
+    fn spread(child: &Chromosome) -> f32 {
+        child.iter().map(|gene| gene.abs()).sum::<f32>() / child.len() as f32
+    }
+
+    #[test]
+    fn test_spread() {
+        // Half the time `beta <= 1.0` (child between parents), half the time
+        // `beta >= 1.0` (child beyond them), so children spread about as
+        // much as their parents; for `eta = 2` the mean of `|beta|` is
+        // `(1/2) * 3/4 + (1/2) * 3/2 = 1.125`
+        let child = child(2.0);
+        let mean = child.iter().sum::<f32>() / child.len() as f32;
+
+        approx::assert_relative_eq!(mean, 0.0, epsilon = 0.05);
+        approx::assert_relative_eq!(spread(&child), 1.125, epsilon = 0.03);
+
+        // ... but the larger `eta`, the more they concentrate around parents
+        let narrow = spread(&self::child(20.0));
+
+        assert!(narrow > 0.9 && narrow < 1.1);
+        assert!(spread(&self::child(0.5)) > spread(&child));
+    }
+}
@@ -0,0 +1,64 @@
+use rand::{Rng, RngCore};
+
+use crate::{chromosome::Chromosome, CrossoverMethod};
+
+/// Cuts both parents at the same random point and glues the first part of
+/// `parent_a` to the second part of `parent_b`; the cut always falls between
+/// two genes, so each parent contributes at least one of them.
+#[derive(Clone, Debug, Default)]
+pub struct SinglePointCrossover;
+
+impl SinglePointCrossover {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(
+            parent_a.len(),
+            parent_b.len(),
+            "Both parents have to be in the same length"
+        );
+
+        assert!(parent_a.len() >= 2, "Parents need at least two genes");
+
+        let point = rng.gen_range(1..parent_a.len());
+
+        parent_a
+            .iter()
+            .take(point)
+            .chain(parent_b.iter().skip(point))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let parent_a: Chromosome = (1..=8).map(|n| n as f32).collect();
+        let parent_b: Chromosome = (1..=8).map(|n| -n as f32).collect();
+
+        let child = SinglePointCrossover::new().crossover(&mut rng, &parent_a, &parent_b);
+
+        let expected: Chromosome = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, -7.0, -8.0]
+            .into_iter()
+            .collect();
+
+        assert_eq!(child, expected);
+    }
+}
@@ -1,32 +1,90 @@
-use crate::Individual;
+use std::cmp::Ordering;
 
+use crate::{chromosome::Chromosome, Individual};
+
+/// Fitness statistics of a population.
+///
+/// NaN fitness (e.g. of an individual that couldn't be evaluated) is left out
+/// of every metric, apart from `population_size()`; if there's nothing but
+/// NaN, metrics are NaN too.
 #[derive(Clone, Debug)]
 pub struct Statistics {
     min_fitness: f32,
     max_fitness: f32,
     average_fitness: f32,
+    std_dev_fitness: f32,
+    best_index: usize,
+    population_size: usize,
+
+    /// Fitness of the whole population (NaN excluded), from the lowest one;
+    /// kept around so that any percentile can be asked for
+    sorted_fitness: Vec<f32>,
+
+    diversity: Option<Diversity>,
 }
 
 impl Statistics {
+    /// Fitness-only statistics; `diversity()` returns `None` for them
     pub fn new<I>(population: &[I]) -> Self
     where I: Individual {
-        assert!(!population.is_empty());
+        Self::from_fitness(population.iter().map(Individual::fitness))
+    }
+
+    /// Like `new()`, but also measures the population's `Diversity`; that
+    /// compares each pair of chromosomes, so it's opt-in
+    pub fn with_diversity<I>(population: &[I]) -> Self
+    where I: Individual {
+        let chromosomes: Vec<_> = population.iter().map(Individual::chromosome).collect();
+
+        Self {
+            diversity: Some(Diversity::new(&chromosomes)),
+            ..Self::new(population)
+        }
+    }
 
-        let mut min_fitness = population[0].fitness();
-        let mut max_fitness = min_fitness;
-        let mut sum_fitness = 0.0;
+    /// Same as `new()`, but for populations without `Individual`s (e.g.
+    /// NEAT's)
+    pub(crate) fn from_fitness(fitness: impl IntoIterator<Item = f32>) -> Self {
+        let fitness: Vec<f32> = fitness.into_iter().collect();
+        assert!(!fitness.is_empty(), "got empty population");
 
-        for individual in population {
-            let fitness = individual.fitness();
-            min_fitness = min_fitness.min(fitness);
-            max_fitness = max_fitness.max(fitness);
-            sum_fitness += fitness;
+        let population_size = fitness.len();
+        let mut max_fitness = f32::NAN;
+        let mut best_index = 0;
+
+        for (idx, &fitness) in fitness.iter().enumerate() {
+            if fitness > max_fitness || (max_fitness.is_nan() && !fitness.is_nan()) {
+                max_fitness = fitness;
+                best_index = idx;
+            }
         }
 
+        let mut sorted_fitness: Vec<f32> = fitness
+            .into_iter()
+            .filter(|fitness| !fitness.is_nan())
+            .collect();
+
+        sorted_fitness.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let min_fitness = sorted_fitness.first().copied().unwrap_or(f32::NAN);
+        let count = sorted_fitness.len() as f32;
+        let average_fitness = sorted_fitness.iter().sum::<f32>() / count;
+
+        let variance = sorted_fitness
+            .iter()
+            .map(|fitness| (fitness - average_fitness).powi(2))
+            .sum::<f32>()
+            / count;
+
         Self {
             min_fitness,
             max_fitness,
-            average_fitness: sum_fitness / (population.len() as f32),
+            average_fitness,
+            std_dev_fitness: variance.sqrt(),
+            best_index,
+            population_size,
+            sorted_fitness,
+            diversity: None,
         }
     }
 
@@ -41,4 +99,335 @@ impl Statistics {
     pub fn average_fitness(&self) -> f32 {
         self.average_fitness
     }
+
+    pub fn median_fitness(&self) -> f32 {
+        self.percentile(50.0)
+    }
+
+    /// Population (not sample) standard deviation of fitness
+    pub fn std_dev_fitness(&self) -> f32 {
+        self.std_dev_fitness
+    }
+
+    /// Fitness below which given percent of the population falls,
+    /// interpolating linearly between individuals; `percent` must be within
+    /// `0.0..=100.0`
+    pub fn percentile(&self, percent: f32) -> f32 {
+        assert!((0.0..=100.0).contains(&percent));
+
+        if self.sorted_fitness.is_empty() {
+            return f32::NAN;
+        }
+
+        let position = percent / 100.0 * (self.sorted_fitness.len() - 1) as f32;
+        let lower = position.floor() as usize;
+        let upper = position.ceil() as usize;
+        let t = position - lower as f32;
+
+        self.sorted_fitness[lower] + (self.sorted_fitness[upper] - self.sorted_fitness[lower]) * t
+    }
+
+    /// Index (into the population these statistics were computed from) of
+    /// the fittest individual; the first one in case of a tie
+    pub fn best_index(&self) -> usize {
+        self.best_index
+    }
+
+    pub fn population_size(&self) -> usize {
+        self.population_size
+    }
+
+    pub fn diversity(&self) -> Option<&Diversity> {
+        self.diversity.as_ref()
+    }
+}
+
+/// How different chromosomes of a population are from each other; once it
+/// drops close to zero, crossover can't find anything new, and it's all up
+/// to mutation.
+#[derive(Clone, Debug)]
+pub struct Diversity {
+    mean_distance: f32,
+    gene_variance: Vec<f32>,
+}
+
+impl Diversity {
+    /// Chromosomes are expected to be of the same length (extra genes are
+    /// ignored otherwise)
+    pub fn new(chromosomes: &[&Chromosome]) -> Self {
+        let genes = chromosomes.iter().map(|c| c.len()).min().unwrap_or(0);
+        let count = chromosomes.len() as f32;
+
+        let gene_variance = (0..genes)
+            .map(|gene| {
+                let mean = chromosomes.iter().map(|c| c[gene]).sum::<f32>() / count;
+
+                chromosomes
+                    .iter()
+                    .map(|c| (c[gene] - mean).powi(2))
+                    .sum::<f32>()
+                    / count
+            })
+            .collect();
+
+        let mut total_distance = 0.0;
+        let mut pairs = 0;
+
+        for (idx, a) in chromosomes.iter().enumerate() {
+            for b in &chromosomes[idx + 1..] {
+                total_distance += a
+                    .iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| (a - b).powi(2))
+                    .sum::<f32>()
+                    .sqrt();
+
+                pairs += 1;
+            }
+        }
+
+        Self {
+            mean_distance: if pairs == 0 {
+                0.0
+            } else {
+                total_distance / pairs as f32
+            },
+            gene_variance,
+        }
+    }
+
+    /// Average Euclidean distance between every two chromosomes
+    pub fn mean_distance(&self) -> f32 {
+        self.mean_distance
+    }
+
+    /// Population variance of each gene, in order
+    pub fn gene_variance(&self) -> &[f32] {
+        &self.gene_variance
+    }
+
+    pub fn average_gene_variance(&self) -> f32 {
+        if self.gene_variance.is_empty() {
+            0.0
+        } else {
+            self.gene_variance.iter().sum::<f32>() / self.gene_variance.len() as f32
+        }
+    }
+}
+
+/// Statistics of consecutive generations, for keeping an eye on how a run
+/// goes.
+#[derive(Clone, Debug, Default)]
+pub struct StatisticsHistory {
+    generations: Vec<Statistics>,
+}
+
+impl StatisticsHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, stats: Statistics) {
+        self.generations.push(stats);
+    }
+
+    pub fn len(&self) -> usize {
+        self.generations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generations.is_empty()
+    }
+
+    pub fn get(&self, generation: usize) -> Option<&Statistics> {
+        self.generations.get(generation)
+    }
+
+    pub fn last(&self) -> Option<&Statistics> {
+        self.generations.last()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Statistics> {
+        self.generations.iter()
+    }
+
+    /// Generation with the highest `max_fitness()` (the earliest one in case
+    /// of a tie), together with its statistics
+    pub fn best(&self) -> Option<(usize, &Statistics)> {
+        let mut best: Option<(usize, &Statistics)> = None;
+
+        for (generation, stats) in self.generations.iter().enumerate() {
+            let improved = match best {
+                Some((_, best)) => stats.max_fitness() > best.max_fitness(),
+                None => true,
+            };
+
+            if improved {
+                best = Some((generation, stats));
+            }
+        }
+
+        best
+    }
+
+    /// Number of generations since `max_fitness()` last improved; zero if
+    /// the latest generation is the best one so far
+    pub fn stagnation(&self) -> usize {
+        self.best()
+            .map_or(0, |(generation, _)| self.generations.len() - 1 - generation)
+    }
+
+    /// Slope of the least-squares line fitted through `metric` of the last
+    /// `window` generations (or all of them, if there are fewer), i.e. how
+    /// much it changes per generation; `None` for fewer than two
+    /// generations
+    pub fn trend(&self, window: usize, metric: impl Fn(&Statistics) -> f32) -> Option<f32> {
+        let start = self.generations.len().saturating_sub(window);
+        let values: Vec<f32> = self.generations[start..].iter().map(metric).collect();
+
+        if values.len() < 2 {
+            return None;
+        }
+
+        let n = values.len() as f32;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = values.iter().sum::<f32>() / n;
+
+        let (covariance, variance) =
+            values
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(covariance, variance), (x, y)| {
+                    let dx = x as f32 - mean_x;
+                    (covariance + dx * (y - mean_y), variance + dx * dx)
+                });
+
+        Some(covariance / variance)
+    }
+
+    pub fn max_fitness_trend(&self, window: usize) -> Option<f32> {
+        self.trend(window, Statistics::max_fitness)
+    }
+
+    pub fn average_fitness_trend(&self, window: usize) -> Option<f32> {
+        self.trend(window, Statistics::average_fitness)
+    }
+
+    /// Trend of `Diversity::mean_distance()`; generations without diversity
+    /// count as zero
+    pub fn diversity_trend(&self, window: usize) -> Option<f32> {
+        self.trend(window, |stats| {
+            stats.diversity().map_or(0.0, Diversity::mean_distance)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::individual;
+
+    fn stats(fitness: &[f32]) -> Statistics {
+        Statistics::from_fitness(fitness.iter().copied())
+    }
+
+    #[test]
+    fn test_fitness() {
+        let stats = stats(&[4.0, 2.0, 5.0, 4.0, 9.0, 4.0, 7.0, 5.0]);
+
+        approx::assert_relative_eq!(stats.min_fitness(), 2.0);
+        approx::assert_relative_eq!(stats.max_fitness(), 9.0);
+        approx::assert_relative_eq!(stats.average_fitness(), 5.0);
+        approx::assert_relative_eq!(stats.median_fitness(), 4.5);
+        approx::assert_relative_eq!(stats.std_dev_fitness(), 2.0);
+        assert_eq!(stats.best_index(), 4);
+        assert_eq!(stats.population_size(), 8);
+        assert!(stats.diversity().is_none());
+    }
+
+    #[test]
+    fn test_nan_fitness() {
+        let some_nan = stats(&[f32::NAN, 1.0, 3.0, f32::NAN, 2.0]);
+
+        approx::assert_relative_eq!(some_nan.min_fitness(), 1.0);
+        approx::assert_relative_eq!(some_nan.max_fitness(), 3.0);
+        approx::assert_relative_eq!(some_nan.average_fitness(), 2.0);
+        approx::assert_relative_eq!(some_nan.median_fitness(), 2.0);
+        approx::assert_relative_eq!(some_nan.std_dev_fitness(), (2.0f32 / 3.0).sqrt());
+        approx::assert_relative_eq!(some_nan.percentile(0.0), 1.0);
+        approx::assert_relative_eq!(some_nan.percentile(100.0), 3.0);
+        assert_eq!(some_nan.best_index(), 2);
+        assert_eq!(some_nan.population_size(), 5);
+
+        let all_nan = stats(&[f32::NAN, f32::NAN]);
+
+        assert!(all_nan.min_fitness().is_nan());
+        assert!(all_nan.max_fitness().is_nan());
+        assert!(all_nan.average_fitness().is_nan());
+        assert!(all_nan.median_fitness().is_nan());
+        assert_eq!(all_nan.population_size(), 2);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let stats = stats(&[40.0, 10.0, 30.0, 20.0]);
+
+        approx::assert_relative_eq!(stats.percentile(0.0), 10.0);
+        approx::assert_relative_eq!(stats.percentile(25.0), 17.5);
+        approx::assert_relative_eq!(stats.median_fitness(), 25.0);
+        approx::assert_relative_eq!(stats.percentile(90.0), 37.0);
+        approx::assert_relative_eq!(stats.percentile(100.0), 40.0);
+    }
+
+    #[test]
+    fn test_single_individual() {
+        let stats = stats(&[3.0]);
+
+        approx::assert_relative_eq!(stats.median_fitness(), 3.0);
+        approx::assert_relative_eq!(stats.percentile(75.0), 3.0);
+        approx::assert_relative_eq!(stats.std_dev_fitness(), 0.0);
+    }
+
+    #[test]
+    fn test_diversity() {
+        let population = vec![
+            individual(&[0.0, 0.0, 1.0]),
+            individual(&[3.0, 4.0, 1.0]),
+            individual(&[0.0, 4.0, 1.0]),
+        ];
+
+        assert!(Statistics::new(&population).diversity().is_none());
+
+        let stats = Statistics::with_diversity(&population);
+        let diversity = stats.diversity().unwrap();
+
+        // Distances: 5, 4 and 3
+        approx::assert_relative_eq!(diversity.mean_distance(), 4.0);
+        approx::assert_relative_eq!(diversity.gene_variance(), [2.0, 32.0 / 9.0, 0.0].as_ref());
+        approx::assert_relative_eq!(diversity.average_gene_variance(), 50.0 / 27.0);
+        assert_eq!(stats.best_index(), 1);
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = StatisticsHistory::new();
+
+        assert_eq!(history.best().map(|(generation, _)| generation), None);
+        assert_eq!(history.max_fitness_trend(10), None);
+
+        for &(min, max) in &[(0.0, 1.0), (1.0, 3.0), (1.0, 5.0), (2.0, 5.0), (2.0, 4.0)] {
+            history.push(stats(&[min, max]));
+        }
+
+        assert_eq!(history.len(), 5);
+        assert_eq!(history.best().map(|(generation, _)| generation), Some(2));
+        assert_eq!(history.stagnation(), 2);
+
+        // Max fitness over the last three generations: 5, 5, 4
+        approx::assert_relative_eq!(history.max_fitness_trend(3).unwrap(), -0.5);
+
+        // Average fitness over all generations: 0.5, 2, 3, 3.5, 3
+        approx::assert_relative_eq!(history.average_fitness_trend(100).unwrap(), 0.65);
+        approx::assert_relative_eq!(history.diversity_trend(100).unwrap(), 0.0);
+    }
 }
@@ -0,0 +1,363 @@
+use std::cmp::Ordering;
+
+use rand::{prelude::SliceRandom, seq::index, RngCore};
+
+use crate::GeneticAlgorithm;
+use crate::Individual;
+use crate::MutationContext;
+use crate::SelectionMethod;
+
+/// Which individuals `SteadyState` replaces with fresh children.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplacementPolicy {
+    /// The least fit ones
+    #[default]
+    Worst,
+
+    /// The ones that have been in the population for the most steps;
+    /// individuals present from the start count as born before step zero
+    Oldest,
+
+    /// Uniformly random ones
+    Random,
+
+    /// Losers of tournaments between `size` random individuals
+    TournamentLoser { size: usize },
+}
+
+/// Steady-state evolution: instead of replacing the whole population at
+/// once, each `step()` breeds a few children and puts them in place of the
+/// individuals picked by a `ReplacementPolicy`.
+///
+/// The population is modified in place and individuals keep their indices,
+/// which is how `Oldest` keeps track of their age.
+///
+/// For mutation methods, a generation passes once as many children have
+/// been bred as there are individuals; children are expected to be
+/// evaluated before the next `step()`, so that their success rate can be
+/// told.
+#[derive(Clone, Debug)]
+pub struct SteadyState {
+    policy: ReplacementPolicy,
+    offspring: usize,
+    births: Vec<usize>,
+    steps: usize,
+
+    /// For each individual bred by the last step, fitness of its fitter
+    /// parent; `None` for the others
+    parent_fitness: Vec<Option<f32>>,
+
+    /// Number of children bred during the current generation and how many
+    /// of them turned out fitter than their parents
+    bred: usize,
+    successful: usize,
+
+    /// Success rate of the previous generation
+    success_rate: Option<f32>,
+}
+
+impl SteadyState {
+    pub fn new(policy: ReplacementPolicy) -> Self {
+        if let ReplacementPolicy::TournamentLoser { size } = policy {
+            assert!(size > 0, "tournament needs at least one contestant");
+        }
+
+        Self {
+            policy,
+            offspring: 1,
+            births: Vec::new(),
+            steps: 0,
+            parent_fitness: Vec::new(),
+            bred: 0,
+            successful: 0,
+            success_rate: None,
+        }
+    }
+
+    /// Number of children bred each step; one by default
+    pub fn with_offspring(mut self, offspring: usize) -> Self {
+        assert!(offspring > 0);
+
+        self.offspring = offspring;
+        self
+    }
+
+    pub fn policy(&self) -> ReplacementPolicy {
+        self.policy
+    }
+
+    /// Number of steps performed so far
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Breeds children and puts them in place of individuals chosen by the
+    /// policy; returns indices of the replaced individuals
+    pub fn step<S, I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        ga: &mut GeneticAlgorithm<S>,
+        population: &mut [I],
+    ) -> Vec<usize>
+    where
+        S: SelectionMethod,
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+        assert!(
+            self.offspring <= population.len(),
+            "can't replace more individuals than there are"
+        );
+
+        // Individuals present from the start (or added since) are born
+        // before the first step
+        self.births.resize(population.len(), 0);
+        self.parent_fitness.resize(population.len(), None);
+
+        // Children bred by the last step have been evaluated by now
+        for (individual, parent_fitness) in population.iter().zip(&mut self.parent_fitness) {
+            if let Some(parent_fitness) = parent_fitness.take() {
+                self.bred += 1;
+
+                if individual.fitness() > parent_fitness {
+                    self.successful += 1;
+                }
+            }
+        }
+
+        let generation = self.generation(self.steps, population.len());
+        let new_generation =
+            self.steps == 0 || generation != self.generation(self.steps - 1, population.len());
+
+        if new_generation && self.bred > 0 {
+            self.success_rate = Some(self.successful as f32 / self.bred as f32);
+            self.bred = 0;
+            self.successful = 0;
+        }
+
+        let context = MutationContext {
+            generation,
+            success_rate: self.success_rate,
+        };
+
+        if new_generation {
+            ga.adapt(&context);
+        }
+
+        let children = ga.offspring(rng, population, self.offspring, &context);
+        let victims = self.victims(rng, population);
+
+        self.steps += 1;
+
+        for (&victim, (child, parent_fitness)) in victims.iter().zip(children) {
+            population[victim] = child;
+            self.births[victim] = self.steps;
+            self.parent_fitness[victim] = Some(parent_fitness);
+        }
+
+        victims
+    }
+
+    /// Generation that given step belongs to
+    fn generation(&self, step: usize, population_size: usize) -> usize {
+        step * self.offspring / population_size
+    }
+
+    /// Picks `offspring` distinct individuals to be replaced
+    fn victims<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> Vec<usize>
+    where
+        I: Individual,
+    {
+        let fitness = |idx: &usize| population[*idx].fitness();
+        let compare = |a: &usize, b: &usize| {
+            fitness(a)
+                .partial_cmp(&fitness(b))
+                .unwrap_or(Ordering::Equal)
+        };
+
+        let mut indices: Vec<usize> = (0..population.len()).collect();
+
+        match self.policy {
+            ReplacementPolicy::Worst => {
+                indices.sort_by(compare);
+                indices.truncate(self.offspring);
+                indices
+            }
+
+            ReplacementPolicy::Oldest => {
+                indices.sort_by_key(|&idx| self.births[idx]);
+                indices.truncate(self.offspring);
+                indices
+            }
+
+            ReplacementPolicy::Random => {
+                index::sample(rng, population.len(), self.offspring).into_vec()
+            }
+
+            ReplacementPolicy::TournamentLoser { size } => (0..self.offspring)
+                .map(|_| {
+                    let loser = (0..size)
+                        .map(|_| *indices.choose(rng).unwrap())
+                        .min_by(compare)
+                        .unwrap();
+
+                    indices.retain(|&idx| idx != loser);
+                    loser
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::{
+        gaussian_mutation::GaussianMutation,
+        tests::{individual, RecordingMutation, TestIndividual},
+        tournament::TournamentSelection,
+        uniform_crossover::UniformCrossover,
+    };
+
+    fn ga() -> GeneticAlgorithm<TournamentSelection> {
+        GeneticAlgorithm::new(
+            TournamentSelection::new(2, 1.0),
+            UniformCrossover::new(),
+            GaussianMutation::new(0.5, 0.5),
+        )
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            individual(&[1.0, 2.0, 1.0]), // fitness: 4.0
+            individual(&[0.0, 0.0, 0.0]), // fitness: 0.0
+            individual(&[1.0, 2.0, 4.0]), // fitness: 7.0
+            individual(&[1.0, 1.0, 1.0]), // fitness: 3.0
+        ]
+    }
+
+    #[test]
+    fn test_worst() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut steady_state = SteadyState::new(ReplacementPolicy::Worst).with_offspring(2);
+        let mut population = population();
+
+        let replaced = steady_state.step(&mut rng, &mut ga(), &mut population);
+
+        assert_eq!(replaced, vec![1, 3]);
+        assert_eq!(population[0], individual(&[1.0, 2.0, 1.0]));
+        assert_eq!(population[2], individual(&[1.0, 2.0, 4.0]));
+        assert_eq!(steady_state.steps(), 1);
+    }
+
+    #[test]
+    fn test_oldest() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut steady_state = SteadyState::new(ReplacementPolicy::Oldest);
+        let mut population = population();
+
+        let replaced: Vec<_> = (0..6)
+            .flat_map(|_| steady_state.step(&mut rng, &mut ga(), &mut population))
+            .collect();
+
+        assert_eq!(replaced, vec![0, 1, 2, 3, 0, 1]);
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut steady_state = SteadyState::new(ReplacementPolicy::Random).with_offspring(3);
+        let mut population = population();
+
+        for _ in 0..20 {
+            let mut replaced = steady_state.step(&mut rng, &mut ga(), &mut population);
+            replaced.sort_unstable();
+            replaced.dedup();
+
+            assert_eq!(replaced.len(), 3);
+            assert!(replaced.iter().all(|&idx| idx < 4));
+        }
+    }
+
+    #[test]
+    fn test_tournament_loser() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut steady_state = SteadyState::new(ReplacementPolicy::TournamentLoser { size: 2 });
+        let mut histogram = [0; 4];
+
+        for _ in 0..1_000 {
+            for idx in steady_state.step(&mut rng, &mut ga(), &mut population()) {
+                histogram[idx] += 1;
+            }
+        }
+
+        // The less fit an individual is, the more often it loses
+        assert!(histogram[1] > histogram[3]);
+        assert!(histogram[3] > histogram[0]);
+        assert!(histogram[0] > histogram[2]);
+    }
+
+    #[test]
+    fn test_improves() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut steady_state = SteadyState::new(ReplacementPolicy::Worst);
+        let mut population = population();
+
+        let average = |population: &[TestIndividual]| {
+            population.iter().map(Individual::fitness).sum::<f32>() / population.len() as f32
+        };
+
+        let before = average(&population);
+
+        for _ in 0..40 {
+            steady_state.step(&mut rng, &mut ga(), &mut population);
+        }
+
+        assert!(average(&population) > before);
+    }
+
+    #[test]
+    fn test_mutation_context() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mutation = RecordingMutation::default();
+        let contexts = mutation.contexts.clone();
+        let adaptations = mutation.adaptations.clone();
+
+        let mut ga = GeneticAlgorithm::new(
+            TournamentSelection::new(2, 1.0),
+            UniformCrossover::new(),
+            mutation,
+        );
+
+        let mut steady_state = SteadyState::new(ReplacementPolicy::Worst).with_offspring(2);
+        let mut population = population();
+
+        // Two children per step and four individuals make for two steps per
+        // generation
+        for _ in 0..6 {
+            steady_state.step(&mut rng, &mut ga, &mut population);
+        }
+
+        let generations: Vec<_> = contexts
+            .borrow()
+            .iter()
+            .map(|context| context.generation)
+            .collect();
+
+        assert_eq!(generations, vec![0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2]);
+
+        let success_rates: Vec<_> = adaptations
+            .borrow()
+            .iter()
+            .map(|context| (context.generation, context.success_rate))
+            .collect();
+
+        assert_eq!(
+            success_rates,
+            vec![(0, None), (1, Some(1.0)), (2, Some(0.0))]
+        );
+    }
+}
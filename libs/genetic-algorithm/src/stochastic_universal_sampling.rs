@@ -0,0 +1,206 @@
+use rand::{prelude::SliceRandom, Rng, RngCore};
+
+use crate::Individual;
+use crate::SelectionMethod;
+
+/// Fitness-proportional selection, like `RouletteWheelSelection`, but
+/// spinning the wheel just once for the whole batch: individuals get picked
+/// by `count` evenly spaced pointers, so each one is selected within one of
+/// its expected number of times.
+///
+/// Fitness must not be negative; when it sums to zero, everyone gets the
+/// same chance. Selected individuals are returned in random order, so that
+/// consecutive ones can be paired up as parents.
+#[derive(Clone, Debug, Default)]
+pub struct StochasticUniversalSampling;
+
+impl StochasticUniversalSampling {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SelectionMethod for StochasticUniversalSampling {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(
+            !population.is_empty(),
+            "got empty population, not going to work"
+        );
+
+        let mut weights: Vec<f32> = population.iter().map(|i| i.fitness()).collect();
+
+        assert!(
+            weights.iter().all(|&weight| weight >= 0.0),
+            "got negative fitness, not going to work"
+        );
+
+        let mut total: f32 = weights.iter().sum();
+
+        if total <= 0.0 {
+            weights.iter_mut().for_each(|weight| *weight = 1.0);
+            total = weights.len() as f32;
+        }
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let step = total / count as f32;
+        let mut pointer = rng.gen_range(0.0..step);
+        let mut cumulative = 0.0;
+        let mut selected = Vec::with_capacity(count);
+
+        for (individual, weight) in population.iter().zip(&weights) {
+            cumulative += weight;
+
+            while selected.len() < count && pointer < cumulative {
+                selected.push(individual);
+                pointer += step;
+            }
+        }
+
+        // Rounding errors might leave the last pointer just past the end
+        if selected.len() < count {
+            let (last, _) = population
+                .iter()
+                .zip(&weights)
+                .rev()
+                .find(|(_, &weight)| weight > 0.0)
+                .unwrap();
+
+            selected.resize(count, last);
+        }
+
+        selected.shuffle(rng);
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, iter::FromIterator};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::tests::TestIndividual;
+
+    fn histogram(population: &[TestIndividual], count: usize) -> BTreeMap<i32, usize> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        StochasticUniversalSampling::new()
+            .select_many(&mut rng, population, count)
+            .into_iter()
+            .fold(Default::default(), |mut histogram, individual| {
+                *histogram.entry(individual.fitness() as _).or_default() += 1;
+                histogram
+            })
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(3.0),
+        ]
+    }
+
+    #[test]
+    fn test() {
+        // Expected counts are whole numbers, so there's no room for luck
+        let actual_histogram = histogram(&population(), 1_000);
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // fitness, count
+            (1, 100),
+            (2, 200),
+            (3, 300),
+            (4, 400),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn test_minimal_spread() {
+        for count in 1..50 {
+            let actual_histogram = histogram(&population(), count);
+
+            for (&fitness, &actual) in &actual_histogram {
+                let expected = count as f32 * fitness as f32 / 10.0;
+
+                assert!(
+                    (actual as f32 - expected).abs() < 1.0,
+                    "fitness {} got picked {} times out of {}",
+                    fitness,
+                    actual,
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_fitness() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let population = vec![TestIndividual::new(0.0); 4];
+
+        // All individuals look the same, so they're told apart by their
+        // position in `population`
+        let actual_histogram = StochasticUniversalSampling::new()
+            .select_many(&mut rng, &population, 8)
+            .into_iter()
+            .fold(BTreeMap::new(), |mut histogram, individual| {
+                let idx = population
+                    .iter()
+                    .position(|other| std::ptr::eq(other, individual))
+                    .unwrap();
+
+                *histogram.entry(idx).or_default() += 1;
+                histogram
+            });
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // index, count
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (3, 2),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn test_shuffled() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let actual: Vec<i32> = StochasticUniversalSampling::new()
+            .select_many(&mut rng, &population(), 10)
+            .into_iter()
+            .map(|individual| individual.fitness() as _)
+            .collect();
+
+        let mut sorted = actual.clone();
+        sorted.sort_by_key(|&fitness| [2, 1, 4, 3].iter().position(|&f| f == fitness));
+
+        assert_ne!(actual, sorted);
+    }
+}
@@ -0,0 +1,159 @@
+use std::cmp::Ordering;
+
+use rand::{prelude::SliceRandom, Rng, RngCore};
+
+use crate::Individual;
+use crate::SelectionMethod;
+
+/// Picks `size` random individuals (possibly repeating some) and lets them
+/// compete: the fittest one wins with `probability`, otherwise the second
+/// fittest one wins with `probability`, and so on - the least fit one wins
+/// whenever everyone else has lost.
+///
+/// Unlike `RouletteWheelSelection`, only the order of fitness matters, so it
+/// works just as well for populations with zero or negative fitness.
+#[derive(Clone, Debug)]
+pub struct TournamentSelection {
+    size: usize,
+    probability: f32,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize, probability: f32) -> Self {
+        assert!(size > 0, "tournament needs at least one contestant");
+        assert!((0.0..=1.0).contains(&probability));
+
+        Self { size, probability }
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        let mut contestants: Vec<&I> = (0..self.size)
+            .map(|_| {
+                population
+                    .choose(rng)
+                    .expect("got empty population, not going to work")
+            })
+            .collect();
+
+        contestants.sort_by(|a, b| {
+            b.fitness()
+                .partial_cmp(&a.fitness())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let last = contestants.pop().unwrap();
+
+        contestants
+            .into_iter()
+            .find(|_| rng.gen_bool(self.probability as _))
+            .unwrap_or(last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, iter::FromIterator};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::tests::TestIndividual;
+
+    fn histogram(
+        method: TournamentSelection,
+        population: &[TestIndividual],
+    ) -> BTreeMap<i32, usize> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        (0..1_000)
+            .map(|_| method.select(&mut rng, population))
+            .fold(Default::default(), |mut histogram, individual| {
+                *histogram.entry(individual.fitness() as _).or_default() += 1;
+                histogram
+            })
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(3.0),
+        ]
+    }
+
+    #[test]
+    fn test() {
+        // Best of two: the fittest individual wins unless it wasn't drawn,
+        // so it gets picked 7/16 of the time
+        let actual_histogram = histogram(TournamentSelection::new(2, 1.0), &population());
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // fitness, count
+            (1, 66),
+            (2, 179),
+            (3, 299),
+            (4, 456),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn test_probability() {
+        let actual_histogram = histogram(TournamentSelection::new(3, 0.75), &population());
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // fitness, count
+            (1, 70),
+            (2, 146),
+            (3, 293),
+            (4, 491),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn test_single_contestant() {
+        // With no one to compete with, selection is just uniformly random
+        let actual_histogram = histogram(TournamentSelection::new(1, 1.0), &population());
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // fitness, count
+            (1, 262),
+            (2, 246),
+            (3, 238),
+            (4, 254),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn test_zero_and_negative_fitness() {
+        let population = vec![
+            TestIndividual::new(0.0),
+            TestIndividual::new(-1.0),
+            TestIndividual::new(0.0),
+            TestIndividual::new(-3.0),
+        ];
+
+        let actual_histogram = histogram(TournamentSelection::new(2, 1.0), &population);
+
+        let expected_histogram = BTreeMap::from_iter(vec![
+            // fitness, count
+            (-3, 62),
+            (-1, 204),
+            (0, 734),
+        ]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+}
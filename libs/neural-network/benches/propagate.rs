@@ -0,0 +1,76 @@
+//! Compares the scalar and the `simd` implementations of propagation:
+//!
+//! ```text
+//! cargo bench -p lib-neural-network --bench propagate -- --save-baseline scalar
+//! cargo bench -p lib-neural-network --features simd --bench propagate -- --baseline scalar
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use lib_neural_network::{BatchBuffers, LayerTopology, MatrixNetwork, Network};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+const SIZES: &[usize] = &[8, 32, 128, 512];
+const BATCH: usize = 64;
+
+fn network(size: usize) -> Network {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+    Network::random(
+        &mut rng,
+        &[
+            LayerTopology::new(size),
+            LayerTopology::new(size),
+            LayerTopology::new(size),
+        ],
+    )
+}
+
+fn inputs(len: usize) -> Vec<f32> {
+    let mut rng = ChaCha8Rng::from_seed([1; 32]);
+
+    (0..len).map(|_| rng.gen_range(-1.0..=1.0)).collect()
+}
+
+fn propagate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("propagate");
+
+    for &size in SIZES {
+        let network = network(size);
+        let inputs = inputs(size);
+
+        group.throughput(Throughput::Elements((2 * size * size) as u64));
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| network.propagate(black_box(inputs.clone())))
+        });
+    }
+
+    group.finish();
+}
+
+fn propagate_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("propagate_batch");
+
+    for &size in SIZES {
+        let network = MatrixNetwork::new(&network(size)).unwrap();
+        let inputs = inputs(BATCH * size);
+        let mut outputs = vec![0.0; BATCH * size];
+        let mut buffers = BatchBuffers::new();
+
+        group.throughput(Throughput::Elements((BATCH * 2 * size * size) as u64));
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                network
+                    .propagate_batch(black_box(&inputs), &mut outputs, &mut buffers)
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, propagate, propagate_batch);
+criterion_main!(benches);
@@ -0,0 +1,154 @@
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Activation {
+    /// `max(0, x)` - the default, and the only activation networks used to have
+    #[default]
+    Relu,
+
+    /// Like `Relu`, but negative values are scaled by the given slope instead
+    /// of being clamped to zero
+    LeakyRelu(f32),
+
+    /// `1 / (1 + e^-x)`, squashes everything into `(0, 1)`
+    Sigmoid,
+
+    /// Squashes everything into `(-1, 1)`
+    Tanh,
+
+    /// Passes the value through untouched
+    Identity,
+
+    /// `x / (1 + |x|)`, a cheaper alternative to `Tanh`
+    Softsign,
+
+    /// User-supplied function; can't be serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(f32) -> f32),
+}
+
+impl Activation {
+    pub fn apply(&self, x: f32) -> f32 {
+        match *self {
+            Self::Relu => x.max(0.0),
+            Self::LeakyRelu(slope) => {
+                if x > 0.0 {
+                    x
+                } else {
+                    slope * x
+                }
+            }
+            Self::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Self::Tanh => x.tanh(),
+            Self::Identity => x,
+            Self::Softsign => x / (1.0 + x.abs()),
+            Self::Custom(f) => f(x),
+        }
+    }
+
+    /// Derivative of `apply()` at `x`; for `Custom` it's approximated
+    /// numerically, since there's no way to differentiate an arbitrary `fn`
+    pub fn derivative(&self, x: f32) -> f32 {
+        match *self {
+            Self::Relu => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::LeakyRelu(slope) => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    slope
+                }
+            }
+            Self::Sigmoid => {
+                let y = self.apply(x);
+                y * (1.0 - y)
+            }
+            Self::Tanh => 1.0 - x.tanh().powi(2),
+            Self::Identity => 1.0,
+            Self::Softsign => 1.0 / (1.0 + x.abs()).powi(2),
+            Self::Custom(f) => {
+                const H: f32 = 1e-3;
+                (f(x + H) - f(x - H)) / (2.0 * H)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relu() {
+        approx::assert_relative_eq!(Activation::Relu.apply(-2.0), 0.0);
+        approx::assert_relative_eq!(Activation::Relu.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_leaky_relu() {
+        approx::assert_relative_eq!(Activation::LeakyRelu(0.1).apply(-2.0), -0.2);
+        approx::assert_relative_eq!(Activation::LeakyRelu(0.1).apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_sigmoid() {
+        approx::assert_relative_eq!(Activation::Sigmoid.apply(0.0), 0.5);
+        approx::assert_relative_eq!(Activation::Sigmoid.apply(2.0), 0.880797);
+        approx::assert_relative_eq!(Activation::Sigmoid.apply(-2.0), 0.11920292);
+    }
+
+    #[test]
+    fn test_tanh() {
+        approx::assert_relative_eq!(Activation::Tanh.apply(0.0), 0.0);
+        approx::assert_relative_eq!(Activation::Tanh.apply(-1.0), -0.7615942);
+    }
+
+    #[test]
+    fn test_identity() {
+        approx::assert_relative_eq!(Activation::Identity.apply(-3.5), -3.5);
+    }
+
+    #[test]
+    fn test_softsign() {
+        approx::assert_relative_eq!(Activation::Softsign.apply(1.0), 0.5);
+        approx::assert_relative_eq!(Activation::Softsign.apply(-3.0), -0.75);
+    }
+
+    #[test]
+    fn test_derivative() {
+        let activations = [
+            Activation::Relu,
+            Activation::LeakyRelu(0.1),
+            Activation::Sigmoid,
+            Activation::Tanh,
+            Activation::Identity,
+            Activation::Softsign,
+            Activation::Custom(|x| x * x),
+        ];
+
+        for activation in &activations {
+            for &x in &[-2.0, -0.5, 0.3, 1.7] {
+                let h = 1e-2;
+                let expected = (activation.apply(x + h) - activation.apply(x - h)) / (2.0 * h);
+
+                approx::assert_relative_eq!(
+                    activation.derivative(x),
+                    expected,
+                    epsilon = 1e-3,
+                    max_relative = 1e-2
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom() {
+        let activation = Activation::Custom(|x| x * x);
+
+        approx::assert_relative_eq!(activation.apply(-3.0), 9.0);
+    }
+}
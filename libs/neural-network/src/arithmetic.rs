@@ -0,0 +1,314 @@
+//! Operations on whole networks, treating their weights as vectors.
+//!
+//! All of them require networks to have the same topology (down to
+//! activations and layer kinds) and return
+//! `NetworkError::IncompatibleTopology` otherwise.
+
+use crate::{layer::Layer, neuron::Neuron, Activation, Network, NetworkError};
+
+impl Network {
+    /// Averages given networks, weighting each by the number paired with it;
+    /// weights don't have to add up to one, but they must be finite and not
+    /// negative, and at least one of them must be positive
+    pub fn weighted_average(networks: &[(&Network, f32)]) -> Result<Network, NetworkError> {
+        let &(first, _) = networks.first().ok_or(NetworkError::NoNetworks)?;
+
+        if let Some(idx) = networks
+            .iter()
+            .position(|&(_, weight)| !(weight.is_finite() && weight >= 0.0))
+        {
+            return Err(NetworkError::InvalidWeight { network: idx });
+        }
+
+        let total: f32 = networks.iter().map(|&(_, weight)| weight).sum();
+
+        if !(total.is_finite() && total > 0.0) {
+            return Err(NetworkError::InvalidTotalWeight);
+        }
+
+        for (idx, &(network, _)) in networks.iter().enumerate().skip(1) {
+            first.check_compatible(network, idx)?;
+        }
+
+        let mut average = first.clone();
+        average.weights_mut().for_each(|weight| *weight = 0.0);
+
+        for &(network, weight) in networks {
+            for (sum, value) in average.weights_mut().zip(network.weights()) {
+                *sum += value * weight / total;
+            }
+        }
+
+        Ok(average)
+    }
+
+    /// Linear interpolation: `t = 0.0` returns copy of this network,
+    /// `t = 1.0` - of `other`
+    pub fn lerp(&self, other: &Network, t: f32) -> Result<Network, NetworkError> {
+        self.zip_weights(other, |a, b| a + (b - a) * t)
+    }
+
+    /// Network whose weights are `self - other`; mostly useful for analysing
+    /// what changed between two generations
+    pub fn difference(&self, other: &Network) -> Result<Network, NetworkError> {
+        self.zip_weights(other, |a, b| a - b)
+    }
+
+    /// Euclidean distance between weights of both networks
+    pub fn l2_distance(&self, other: &Network) -> Result<f32, NetworkError> {
+        self.check_compatible(other, 1)?;
+
+        Ok(self
+            .weights()
+            .zip(other.weights())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt())
+    }
+
+    /// `1 - cos(angle)` between weights of both networks, i.e. zero for
+    /// networks pointing the same way, one for orthogonal ones and two for
+    /// opposite ones; a network with all weights zeroed is treated as
+    /// orthogonal to everything
+    pub fn cosine_distance(&self, other: &Network) -> Result<f32, NetworkError> {
+        self.check_compatible(other, 1)?;
+
+        let (dot, norm_a, norm_b) = self
+            .weights()
+            .zip(other.weights())
+            .fold((0.0f32, 0.0f32, 0.0f32), |(dot, norm_a, norm_b), (a, b)| {
+                (dot + a * b, norm_a + a * a, norm_b + b * b)
+            });
+
+        let norm = (norm_a * norm_b).sqrt();
+
+        if norm == 0.0 {
+            Ok(1.0)
+        } else {
+            Ok(1.0 - (dot / norm).clamp(-1.0, 1.0))
+        }
+    }
+
+    fn zip_weights(
+        &self,
+        other: &Network,
+        f: impl Fn(f32, f32) -> f32,
+    ) -> Result<Network, NetworkError> {
+        self.check_compatible(other, 1)?;
+
+        let mut result = self.clone();
+
+        for (weight, other) in result.weights_mut().zip(other.weights()) {
+            *weight = f(*weight, other);
+        }
+
+        Ok(result)
+    }
+
+    /// Makes sure `other` has the same topology as `self`; `idx` is what the
+    /// error reports as `other`'s position
+    fn check_compatible(&self, other: &Network, idx: usize) -> Result<(), NetworkError> {
+        let compatible = self.layers.len() == other.layers.len()
+            && self
+                .layers
+                .iter()
+                .zip(&other.layers)
+                .all(|(a, b)| same_shape(a, b));
+
+        if compatible {
+            Ok(())
+        } else {
+            Err(NetworkError::IncompatibleTopology { network: idx })
+        }
+    }
+}
+
+fn same_shape(a: &Layer, b: &Layer) -> bool {
+    a.kind == b.kind
+        && same_activation(a.activation, b.activation)
+        && same_neurons(&a.neurons, &b.neurons)
+        && same_neurons(&a.gates, &b.gates)
+}
+
+fn same_neurons(a: &[Neuron], b: &[Neuron]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| a.weights.len() == b.weights.len())
+}
+
+fn same_activation(a: Activation, b: Activation) -> bool {
+    match (a, b) {
+        (Activation::LeakyRelu(a), Activation::LeakyRelu(b)) => a == b,
+        (Activation::Custom(a), Activation::Custom(b)) => a as usize == b as usize,
+        (a, b) => std::mem::discriminant(&a) == std::mem::discriminant(&b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LayerKind, LayerTopology};
+
+    fn layers() -> [LayerTopology; 3] {
+        [
+            LayerTopology::new(2),
+            LayerTopology::new(1),
+            LayerTopology::new(1),
+        ]
+    }
+
+    fn network(weights: [f32; 5]) -> Network {
+        Network::from_weights(&layers(), weights.to_vec())
+    }
+
+    fn weights(network: &Network) -> Vec<f32> {
+        network.weights().collect()
+    }
+
+    #[test]
+    fn test_weighted_average() {
+        let a = network([1.0, 2.0, 3.0, 4.0, 5.0]);
+        let b = network([3.0, 2.0, 1.0, 0.0, -1.0]);
+        let c = network([0.0; 5]);
+
+        let actual = Network::weighted_average(&[(&a, 1.0), (&b, 1.0)]).unwrap();
+        approx::assert_relative_eq!(weights(&actual).as_slice(), [2.0; 5].as_ref());
+
+        let actual = Network::weighted_average(&[(&a, 2.0), (&b, 0.0), (&c, 2.0)]).unwrap();
+        approx::assert_relative_eq!(
+            weights(&actual).as_slice(),
+            [0.5, 1.0, 1.5, 2.0, 2.5].as_ref()
+        );
+    }
+
+    #[test]
+    fn test_weighted_average_invalid_weights() {
+        let a = network([1.0; 5]);
+
+        assert_eq!(
+            Network::weighted_average(&[]).unwrap_err(),
+            NetworkError::NoNetworks
+        );
+
+        for &weight in &[-1.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(
+                Network::weighted_average(&[(&a, 1.0), (&a, weight)]).unwrap_err(),
+                NetworkError::InvalidWeight { network: 1 }
+            );
+        }
+
+        assert_eq!(
+            Network::weighted_average(&[(&a, 0.0), (&a, 0.0)]).unwrap_err(),
+            NetworkError::InvalidTotalWeight
+        );
+
+        assert_eq!(
+            Network::weighted_average(&[(&a, f32::MAX), (&a, f32::MAX)]).unwrap_err(),
+            NetworkError::InvalidTotalWeight
+        );
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = network([1.0, 2.0, 3.0, 4.0, 5.0]);
+        let b = network([3.0, 2.0, 1.0, 0.0, -1.0]);
+
+        approx::assert_relative_eq!(
+            weights(&a.lerp(&b, 0.0).unwrap()).as_slice(),
+            weights(&a).as_slice()
+        );
+
+        approx::assert_relative_eq!(
+            weights(&a.lerp(&b, 1.0).unwrap()).as_slice(),
+            weights(&b).as_slice()
+        );
+
+        approx::assert_relative_eq!(
+            weights(&a.lerp(&b, 0.25).unwrap()).as_slice(),
+            [1.5, 2.0, 2.5, 3.0, 3.5].as_ref()
+        );
+    }
+
+    #[test]
+    fn test_difference() {
+        let a = network([1.0, 2.0, 3.0, 4.0, 5.0]);
+        let b = network([3.0, 2.0, 1.0, 0.0, -1.0]);
+
+        approx::assert_relative_eq!(
+            weights(&a.difference(&b).unwrap()).as_slice(),
+            [-2.0, 0.0, 2.0, 4.0, 6.0].as_ref()
+        );
+    }
+
+    #[test]
+    fn test_l2_distance() {
+        let a = network([1.0, 2.0, 3.0, 4.0, 5.0]);
+        let b = network([1.0, 5.0, 3.0, 0.0, 5.0]);
+
+        approx::assert_relative_eq!(a.l2_distance(&b).unwrap(), 5.0);
+        approx::assert_relative_eq!(a.l2_distance(&a).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_cosine_distance() {
+        let a = network([1.0, 0.0, 0.0, 0.0, 0.0]);
+        let b = network([0.0, 2.0, 0.0, 0.0, 0.0]);
+        let c = network([3.0, 0.0, 0.0, 0.0, 0.0]);
+        let d = network([-1.0, 0.0, 0.0, 0.0, 0.0]);
+        let zero = network([0.0; 5]);
+
+        approx::assert_relative_eq!(a.cosine_distance(&b).unwrap(), 1.0);
+        approx::assert_relative_eq!(a.cosine_distance(&c).unwrap(), 0.0);
+        approx::assert_relative_eq!(a.cosine_distance(&d).unwrap(), 2.0);
+        approx::assert_relative_eq!(a.cosine_distance(&zero).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_incompatible_topology() {
+        let a = network([0.0; 5]);
+
+        let different_size = Network::from_weights(
+            &[LayerTopology::new(1), LayerTopology::new(2)],
+            vec![0.0; 4],
+        );
+
+        let different_activation = Network::from_weights(
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_activation(Activation::Tanh),
+            ],
+            vec![0.0; 5],
+        );
+
+        let different_kind = Network::from_weights(
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_kind(LayerKind::Elman),
+            ],
+            vec![0.0; 6],
+        );
+
+        for other in &[different_size, different_activation, different_kind] {
+            let expected = NetworkError::IncompatibleTopology { network: 1 };
+
+            assert_eq!(a.lerp(other, 0.5).unwrap_err(), expected);
+            assert_eq!(a.difference(other).unwrap_err(), expected);
+            assert_eq!(a.l2_distance(other).unwrap_err(), expected);
+            assert_eq!(a.cosine_distance(other).unwrap_err(), expected);
+        }
+
+        let b = network([0.0; 5]);
+        let c = Network::from_weights(
+            &[LayerTopology::new(1), LayerTopology::new(2)],
+            vec![0.0; 4],
+        );
+
+        assert_eq!(
+            Network::weighted_average(&[(&a, 1.0), (&b, 1.0), (&c, 1.0)]).unwrap_err(),
+            NetworkError::IncompatibleTopology { network: 2 }
+        );
+    }
+}
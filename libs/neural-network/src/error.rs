@@ -0,0 +1,208 @@
+use std::{error::Error, fmt};
+
+use crate::LayerKind;
+
+/// Everything that can go wrong when building or running a `Network`.
+///
+/// Layer indices don't count the input layer, i.e. `layer: 0` refers to the
+/// first layer that actually has neurons.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NetworkError {
+    NotEnoughLayers {
+        actual: usize,
+    },
+
+    NotEnoughWeights {
+        layer: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// There were weights left after filling all the layers; how many isn't
+    /// known, since the weights might come from an endless iterator
+    TooManyWeights {
+        expected: usize,
+    },
+
+    /// Layer is so large that the number of weights it needs doesn't even
+    /// fit in `usize`
+    TopologyTooLarge {
+        layer: usize,
+    },
+
+    InvalidInputSize {
+        layer: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    InvalidOutputSize {
+        expected: usize,
+        actual: usize,
+    },
+
+    InvalidState {
+        layer: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    UnsupportedLayerKind {
+        layer: usize,
+        kind: LayerKind,
+    },
+
+    /// Layer uses `Activation::Custom`, which can't be converted into other
+    /// representations
+    UnsupportedActivation {
+        layer: usize,
+    },
+
+    /// Network at given position (e.g. in `Network::weighted_average()`) has
+    /// different topology than the first one
+    IncompatibleTopology {
+        network: usize,
+    },
+
+    /// Operation needs at least one network, but got none
+    NoNetworks,
+
+    /// Network at given position was paired with a negative or non-finite
+    /// weight
+    InvalidWeight {
+        network: usize,
+    },
+
+    /// Weights paired with networks don't add up to a positive, finite number
+    InvalidTotalWeight,
+}
+
+impl NetworkError {
+    /// Moves error reported by a standalone layer into given layer of the
+    /// network
+    crate fn at_layer(self, layer: usize) -> Self {
+        match self {
+            Self::NotEnoughWeights {
+                expected, actual, ..
+            } => Self::NotEnoughWeights {
+                layer,
+                expected,
+                actual,
+            },
+
+            Self::InvalidInputSize {
+                expected, actual, ..
+            } => Self::InvalidInputSize {
+                layer,
+                expected,
+                actual,
+            },
+
+            Self::InvalidState {
+                expected, actual, ..
+            } => Self::InvalidState {
+                layer,
+                expected,
+                actual,
+            },
+
+            Self::UnsupportedLayerKind { kind, .. } => Self::UnsupportedLayerKind { layer, kind },
+            Self::TopologyTooLarge { .. } => Self::TopologyTooLarge { layer },
+
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughLayers { actual } => write!(
+                f,
+                "got {} layers, but networks need at least two of them",
+                actual
+            ),
+
+            Self::NotEnoughWeights {
+                layer,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "got not enough weights: ran out at layer {} after {} weights, but {} were expected",
+                layer, actual, expected
+            ),
+
+            Self::TooManyWeights { expected } => write!(
+                f,
+                "got too many weights: expected exactly {}",
+                expected
+            ),
+
+            Self::InvalidInputSize {
+                layer,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "layer {} got {} inputs, but {} inputs were expected",
+                layer, actual, expected
+            ),
+
+            Self::InvalidOutputSize { expected, actual } => write!(
+                f,
+                "got room for {} outputs, but {} outputs were expected",
+                actual, expected
+            ),
+
+            Self::InvalidState {
+                layer,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "got state of {} values for layer {}, but {} values were expected",
+                actual, layer, expected
+            ),
+
+            Self::UnsupportedLayerKind { layer, kind } => write!(
+                f,
+                "layer {} is {:?}, which isn't supported here",
+                layer, kind
+            ),
+
+            Self::UnsupportedActivation { layer } => write!(
+                f,
+                "layer {} uses a custom activation, which isn't supported here",
+                layer
+            ),
+
+            Self::TopologyTooLarge { layer } => write!(
+                f,
+                "layer {} is too large: number of its weights overflows",
+                layer
+            ),
+
+            Self::IncompatibleTopology { network } => write!(
+                f,
+                "network {} has different topology than the first one",
+                network
+            ),
+
+            Self::NoNetworks => write!(f, "got no networks"),
+
+            Self::InvalidWeight { network } => write!(
+                f,
+                "network {} has negative or non-finite weight",
+                network
+            ),
+
+            Self::InvalidTotalWeight => write!(
+                f,
+                "weights of networks don't add up to a positive, finite number"
+            ),
+        }
+    }
+}
+
+impl Error for NetworkError {}
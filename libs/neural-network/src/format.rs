@@ -0,0 +1,353 @@
+use std::{convert::TryFrom, error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Activation, LayerTopology, Network, NetworkError};
+
+/// Version of the on-disk format written by `Network::to_json()` and
+/// `Network::to_bytes()`; bumped whenever the layout changes in a way older
+/// readers wouldn't understand.
+///
+/// - 1: initial version
+/// - 2: added `kind` to each layer's topology (missing means feed-forward)
+pub const FORMAT_VERSION: u32 = 2;
+
+/// What a `Network` looks like once serialized: its topology and weights, in
+/// the same order as `Network::weights()` yields them
+#[derive(Serialize, Deserialize)]
+crate struct NetworkFile {
+    version: u32,
+    topology: Vec<LayerTopology>,
+    weights: Vec<f32>,
+}
+
+/// Version 1 of `NetworkFile`, from before layers had `kind`; JSON gets away
+/// with `serde(default)` for it, but bincode isn't self-describing, so binary
+/// files of this version have to be read as this struct instead
+#[derive(Deserialize)]
+struct NetworkFileV1 {
+    version: u32,
+    topology: Vec<LayerTopologyV1>,
+    weights: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct LayerTopologyV1 {
+    neurons: usize,
+    activation: Activation,
+}
+
+impl From<NetworkFileV1> for NetworkFile {
+    fn from(file: NetworkFileV1) -> Self {
+        Self {
+            version: file.version,
+            topology: file
+                .topology
+                .into_iter()
+                .map(|layer| LayerTopology::new(layer.neurons).with_activation(layer.activation))
+                .collect(),
+            weights: file.weights,
+        }
+    }
+}
+
+impl From<Network> for NetworkFile {
+    fn from(network: Network) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            topology: network.topology(),
+            weights: network.weights().collect(),
+        }
+    }
+}
+
+impl TryFrom<NetworkFile> for Network {
+    type Error = FormatError;
+
+    fn try_from(file: NetworkFile) -> Result<Self, Self::Error> {
+        if file.version == 0 || file.version > FORMAT_VERSION {
+            return Err(FormatError::UnsupportedVersion {
+                expected: FORMAT_VERSION,
+                actual: file.version,
+            });
+        }
+
+        Ok(Network::try_from_weights(&file.topology, file.weights)?)
+    }
+}
+
+impl Network {
+    pub fn to_json(&self) -> Result<String, FormatError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, FormatError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, FormatError> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Reads networks written by `to_bytes()` of any supported version
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FormatError> {
+        // Each version starts with the version number, so it can be peeked
+        // at before knowing what follows
+        let version: u32 = bincode::deserialize(bytes)?;
+
+        if version == 1 {
+            let file: NetworkFileV1 = bincode::deserialize(bytes)?;
+            Ok(Network::try_from(NetworkFile::from(file))?)
+        } else {
+            Ok(bincode::deserialize(bytes)?)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FormatError {
+    UnsupportedVersion { expected: u32, actual: u32 },
+    Network(NetworkError),
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion { expected, actual } => write!(
+                f,
+                "got network in format version {}, but only versions up to {} are supported",
+                actual, expected
+            ),
+            Self::Network(err) => write!(f, "got invalid network: {}", err),
+            Self::Json(err) => write!(f, "couldn't (de)serialize JSON: {}", err),
+            Self::Binary(err) => write!(f, "couldn't (de)serialize bytes: {}", err),
+        }
+    }
+}
+
+impl Error for FormatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::UnsupportedVersion { .. } => None,
+            Self::Network(err) => Some(err),
+            Self::Json(err) => Some(err),
+            Self::Binary(err) => Some(err),
+        }
+    }
+}
+
+impl From<NetworkError> for FormatError {
+    fn from(err: NetworkError) -> Self {
+        Self::Network(err)
+    }
+}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<bincode::Error> for FormatError {
+    fn from(err: bincode::Error) -> Self {
+        Self::Binary(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::LayerKind;
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(4).with_activation(Activation::LeakyRelu(0.01)),
+                LayerTopology::new(2).with_activation(Activation::Tanh),
+            ],
+        )
+    }
+
+    fn assert_same(actual: &Network, expected: &Network) {
+        let actual_weights: Vec<_> = actual.weights().collect();
+        let expected_weights: Vec<_> = expected.weights().collect();
+
+        assert_eq!(actual_weights, expected_weights);
+
+        assert_eq!(
+            format!("{:?}", actual.topology()),
+            format!("{:?}", expected.topology())
+        );
+
+        assert_eq!(
+            actual.propagate(vec![0.3, -0.2, 0.9]),
+            expected.propagate(vec![0.3, -0.2, 0.9])
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let network = network();
+        let json = network.to_json().unwrap();
+
+        assert_same(&Network::from_json(&json).unwrap(), &network);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let network = network();
+        let bytes = network.to_bytes().unwrap();
+
+        assert_same(&Network::from_bytes(&bytes).unwrap(), &network);
+    }
+
+    #[test]
+    fn test_recurrent_round_trip() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(4).with_kind(LayerKind::Gated),
+                LayerTopology::new(2).with_kind(LayerKind::Elman),
+            ],
+        );
+
+        assert_same(
+            &Network::from_json(&network.to_json().unwrap()).unwrap(),
+            &network,
+        );
+        assert_same(
+            &Network::from_bytes(&network.to_bytes().unwrap()).unwrap(),
+            &network,
+        );
+    }
+
+    #[test]
+    fn test_json_layout() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(1).with_activation(Activation::Sigmoid),
+            ],
+            vec![0.5, -1.0, 0.25],
+        );
+
+        assert_eq!(
+            network.to_json().unwrap(),
+            r#"{"version":2,"topology":[{"neurons":2,"activation":"Relu","kind":"FeedForward"},{"neurons":1,"activation":"Sigmoid","kind":"FeedForward"}],"weights":[0.5,-1.0,0.25]}"#
+        );
+    }
+
+    #[test]
+    fn test_weights_follow_from_weights_ordering() {
+        let json = r#"{
+            "version": 1,
+            "topology": [
+                { "neurons": 3, "activation": "Relu" },
+                { "neurons": 2, "activation": { "LeakyRelu": 0.1 } }
+            ],
+            "weights": [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8]
+        }"#;
+
+        let actual = Network::from_json(json).unwrap();
+
+        let expected = Network::from_weights(
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(2).with_activation(Activation::LeakyRelu(0.1)),
+            ],
+            vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8],
+        );
+
+        assert_same(&actual, &expected);
+    }
+
+    #[test]
+    fn test_v1_bytes() {
+        // Same network as in `test_weights_follow_from_weights_ordering()`,
+        // as written by `to_bytes()` of version 1
+        let bytes = [
+            0x01, 0x00, 0x00, 0x00, // version
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // number of layers
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // neurons
+            0x00, 0x00, 0x00, 0x00, // Relu
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // neurons
+            0x01, 0x00, 0x00, 0x00, 0xcd, 0xcc, 0xcc, 0x3d, // LeakyRelu(0.1)
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // number of weights
+            0xcd, 0xcc, 0xcc, 0x3d, // 0.1
+            0xcd, 0xcc, 0x4c, 0x3e, // 0.2
+            0x9a, 0x99, 0x99, 0x3e, // 0.3
+            0xcd, 0xcc, 0xcc, 0x3e, // 0.4
+            0x00, 0x00, 0x00, 0x3f, // 0.5
+            0x9a, 0x99, 0x19, 0x3f, // 0.6
+            0x33, 0x33, 0x33, 0x3f, // 0.7
+            0xcd, 0xcc, 0x4c, 0x3f, // 0.8
+        ];
+
+        let actual = Network::from_bytes(&bytes).unwrap();
+
+        let expected = Network::from_weights(
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(2).with_activation(Activation::LeakyRelu(0.1)),
+            ],
+            vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8],
+        );
+
+        assert_same(&actual, &expected);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let json = r#"{"version":3,"topology":[],"weights":[]}"#;
+
+        assert!(Network::from_json(json)
+            .unwrap_err()
+            .to_string()
+            .contains("format version 3"));
+    }
+
+    #[test]
+    fn test_invalid_weights() {
+        let json = r#"{"version":1,"topology":[{"neurons":2,"activation":"Relu"},{"neurons":1,"activation":"Relu"}],"weights":[0.5]}"#;
+
+        assert!(Network::from_json(json)
+            .unwrap_err()
+            .to_string()
+            .contains("got not enough weights"));
+    }
+
+    #[test]
+    fn test_topology_too_large() {
+        let json = r#"{"version":2,"topology":[{"neurons":18446744073709551615,"activation":"Relu"},{"neurons":2,"activation":"Relu"}],"weights":[0.5]}"#;
+
+        assert!(Network::from_json(json)
+            .unwrap_err()
+            .to_string()
+            .contains("layer 0 is too large"));
+    }
+
+    #[test]
+    fn test_custom_activation_is_not_serializable() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_activation(Activation::Custom(|x| x)),
+            ],
+            vec![0.0, 1.0],
+        );
+
+        assert!(matches!(network.to_json(), Err(FormatError::Json(_))));
+    }
+}
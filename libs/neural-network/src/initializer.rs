@@ -0,0 +1,136 @@
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, Normal};
+
+/// How `Network::random()` picks starting biases and weights of a layer.
+///
+/// `fan_in` is the number of inputs each neuron gets (recurrent ones
+/// included), and `fan_out` is the number of neurons in the layer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Initializer {
+    /// Everything uniformly from `-1.0..=1.0` - the default, and the only
+    /// initialization networks used to have
+    #[default]
+    Uniform,
+
+    /// Xavier/Glorot: weights uniformly from `-a..=a`, where
+    /// `a = sqrt(6 / (fan_in + fan_out))`; zeroed biases. Suits `Tanh` and
+    /// `Sigmoid`.
+    Xavier,
+
+    /// He: weights from a normal distribution with standard deviation
+    /// `sqrt(2 / fan_in)`; zeroed biases. Suits `Relu` and `LeakyRelu`.
+    He,
+
+    /// Everything from a normal distribution with given standard deviation
+    Normal(f32),
+
+    /// Everything set to zero
+    Zeros,
+
+    /// Everything set to given value; mostly useful for tests
+    Constant(f32),
+}
+
+impl Initializer {
+    crate fn bias(&self, rng: &mut dyn RngCore) -> f32 {
+        match *self {
+            Self::Uniform => rng.gen_range(-1.0..=1.0),
+            Self::Xavier | Self::He | Self::Zeros => 0.0,
+            Self::Normal(sigma) => normal(rng, sigma),
+            Self::Constant(value) => value,
+        }
+    }
+
+    crate fn weight(&self, rng: &mut dyn RngCore, fan_in: usize, fan_out: usize) -> f32 {
+        match *self {
+            Self::Uniform => rng.gen_range(-1.0..=1.0),
+
+            Self::Xavier => {
+                let limit = (6.0 / (fan_in + fan_out).max(1) as f32).sqrt();
+                rng.gen_range(-limit..=limit)
+            }
+
+            Self::He => normal(rng, (2.0 / fan_in.max(1) as f32).sqrt()),
+            Self::Normal(sigma) => normal(rng, sigma),
+            Self::Zeros => 0.0,
+            Self::Constant(value) => value,
+        }
+    }
+}
+
+fn normal(rng: &mut dyn RngCore, sigma: f32) -> f32 {
+    Normal::new(0.0, sigma)
+        .unwrap_or_else(|err| panic!("invalid standard deviation {}: {}", sigma, err))
+        .sample(rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn weights(initializer: Initializer, fan_in: usize, fan_out: usize) -> Vec<f32> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        (0..10_000)
+            .map(|_| initializer.weight(&mut rng, fan_in, fan_out))
+            .collect()
+    }
+
+    fn std_dev(values: &[f32]) -> f32 {
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+
+        variance.sqrt()
+    }
+
+    #[test]
+    fn test_uniform() {
+        let weights = weights(Initializer::Uniform, 100, 100);
+
+        assert!(weights.iter().all(|w| (-1.0..=1.0).contains(w)));
+        approx::assert_relative_eq!(std_dev(&weights), 1.0 / 3.0f32.sqrt(), epsilon = 0.02);
+    }
+
+    #[test]
+    fn test_xavier() {
+        let weights = weights(Initializer::Xavier, 40, 20);
+
+        // limit = sqrt(6 / 60)
+        assert!(weights.iter().all(|w| w.abs() <= 0.1f32.sqrt()));
+        assert!(weights.iter().any(|w| w.abs() > 0.3));
+        approx::assert_relative_eq!(
+            std_dev(&weights),
+            0.1f32.sqrt() / 3.0f32.sqrt(),
+            epsilon = 0.01
+        );
+    }
+
+    #[test]
+    fn test_he() {
+        let weights = weights(Initializer::He, 50, 10);
+
+        approx::assert_relative_eq!(std_dev(&weights), 0.2, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_normal() {
+        let weights = weights(Initializer::Normal(0.5), 50, 10);
+
+        approx::assert_relative_eq!(std_dev(&weights), 0.5, epsilon = 0.02);
+    }
+
+    #[test]
+    fn test_constant() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        assert_eq!(Initializer::Zeros.bias(&mut rng), 0.0);
+        assert_eq!(Initializer::Zeros.weight(&mut rng, 3, 3), 0.0);
+        assert_eq!(Initializer::Constant(0.25).bias(&mut rng), 0.25);
+        assert_eq!(Initializer::Constant(0.25).weight(&mut rng, 3, 3), 0.25);
+        assert_eq!(Initializer::Xavier.bias(&mut rng), 0.0);
+        assert_eq!(Initializer::He.bias(&mut rng), 0.0);
+    }
+}
@@ -1,41 +1,280 @@
 use rand::RngCore;
 
-use crate::neuron::Neuron;
+use crate::{neuron::Neuron, Activation, LayerTopology, NetworkError};
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayerKind {
+    /// Neurons see only the previous layer's outputs
+    #[default]
+    FeedForward,
+
+    /// Elman-style feedback: neurons see the previous layer's outputs and
+    /// this layer's own outputs from the previous step
+    Elman,
+
+    /// Like `Elman`, but each neuron also has an update gate deciding how
+    /// much of its previous output to keep instead of the new one
+    Gated,
+}
+
+impl LayerKind {
+    pub fn is_recurrent(&self) -> bool {
+        *self != Self::FeedForward
+    }
+
+    /// Number of weights (biases included) a layer of this kind needs; `None`
+    /// if it overflows, which can happen for topologies read from untrusted
+    /// files
+    crate fn weights_count(&self, input_size: usize, output_size: usize) -> Option<usize> {
+        let units = match self {
+            Self::FeedForward | Self::Elman => output_size,
+            Self::Gated => output_size.checked_mul(2)?,
+        };
+
+        let neuron_inputs = if self.is_recurrent() {
+            input_size.checked_add(output_size)?
+        } else {
+            input_size
+        };
+
+        units.checked_mul(neuron_inputs.checked_add(1)?)
+    }
+
+    /// Number of neurons a layer of this kind needs, gates included
+    fn units(&self, output_size: usize) -> usize {
+        match self {
+            Self::FeedForward | Self::Elman => output_size,
+            Self::Gated => 2 * output_size,
+        }
+    }
+
+    /// Number of inputs each neuron gets, recurrent ones included
+    fn neuron_inputs(&self, input_size: usize, output_size: usize) -> usize {
+        if self.is_recurrent() {
+            input_size + output_size
+        } else {
+            input_size
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layer {
     crate neurons: Vec<Neuron>,
+    crate activation: Activation,
+    crate kind: LayerKind,
+
+    /// Update gates, one per neuron; used only by `LayerKind::Gated`
+    crate gates: Vec<Neuron>,
 }
 
 impl Layer {
-    pub fn new(neurons: Vec<Neuron>) -> Self {
-        Self { neurons }
+    pub fn new(neurons: Vec<Neuron>, activation: Activation) -> Self {
+        Self {
+            neurons,
+            activation,
+            kind: LayerKind::FeedForward,
+            gates: Vec::new(),
+        }
     }
 
-    pub fn random(rng: &mut dyn RngCore, input_neurons: usize, output_neurons: usize) -> Self {
-        let neurons = (0..output_neurons)
-            .map(|_| Neuron::random(rng, input_neurons))
+    pub fn random(rng: &mut dyn RngCore, input_neurons: usize, topology: &LayerTopology) -> Self {
+        let neuron_inputs = topology.kind.neuron_inputs(input_neurons, topology.neurons);
+
+        let mut neurons: Vec<_> = (0..topology.kind.units(topology.neurons))
+            .map(|_| Neuron::random(rng, topology.initializer, neuron_inputs, topology.neurons))
             .collect();
 
-        Self { neurons }
+        let gates = neurons.split_off(topology.neurons);
+
+        Self {
+            neurons,
+            activation: topology.activation,
+            kind: topology.kind,
+            gates,
+        }
     }
 
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+    pub fn input_size(&self) -> usize {
         self.neurons
-            .iter()
-            .map(|neuron| neuron.propagate(&inputs))
-            .collect()
+            .first()
+            .map_or(0, |neuron| neuron.weights.len() - self.state_size())
+    }
+
+    pub fn topology(&self) -> LayerTopology {
+        LayerTopology::new(self.neurons.len())
+            .with_activation(self.activation)
+            .with_kind(self.kind)
+    }
+
+    /// State this layer starts with, i.e. what it pretends to have returned
+    /// during the step before the first one
+    crate fn initial_state(&self) -> Vec<f32> {
+        vec![0.0; self.state_size()]
+    }
+
+    crate fn state_size(&self) -> usize {
+        if self.kind.is_recurrent() {
+            self.neurons.len()
+        } else {
+            0
+        }
+    }
+
+    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        if self.kind.is_recurrent() {
+            self.try_propagate(inputs)
+                .unwrap_or_else(|err| panic!("{}", err))
+        } else {
+            self.neurons
+                .iter()
+                .map(|neuron| neuron.propagate(&inputs, self.activation))
+                .collect()
+        }
+    }
+
+    pub fn try_propagate(&self, inputs: Vec<f32>) -> Result<Vec<f32>, NetworkError> {
+        self.try_propagate_with_state(inputs, &mut self.initial_state())
+    }
+
+    /// Propagates inputs and, for recurrent layers, replaces `state` with
+    /// this step's outputs
+    crate fn try_propagate_with_state(
+        &self,
+        inputs: Vec<f32>,
+        state: &mut [f32],
+    ) -> Result<Vec<f32>, NetworkError> {
+        if !self.kind.is_recurrent() {
+            return self
+                .neurons
+                .iter()
+                .map(|neuron| neuron.try_propagate(&inputs, self.activation))
+                .collect();
+        }
+
+        if state.len() != self.state_size() {
+            return Err(NetworkError::InvalidState {
+                layer: 0,
+                expected: self.state_size(),
+                actual: state.len(),
+            });
+        }
+
+        if inputs.len() != self.input_size() {
+            return Err(NetworkError::InvalidInputSize {
+                layer: 0,
+                expected: self.input_size(),
+                actual: inputs.len(),
+            });
+        }
+
+        let mut inputs = inputs;
+        inputs.extend_from_slice(state);
+
+        let outputs: Vec<_> = match self.kind {
+            LayerKind::FeedForward => unreachable!(),
+
+            LayerKind::Elman => self
+                .neurons
+                .iter()
+                .map(|neuron| neuron.propagate(&inputs, self.activation))
+                .collect(),
+
+            LayerKind::Gated => self
+                .neurons
+                .iter()
+                .zip(&self.gates)
+                .zip(state.iter())
+                .map(|((neuron, gate), &previous)| {
+                    let candidate = neuron.propagate(&inputs, self.activation);
+                    let update = gate.propagate(&inputs, Activation::Sigmoid);
+
+                    (1.0 - update) * previous + update * candidate
+                })
+                .collect(),
+        };
+
+        state.copy_from_slice(&outputs);
+
+        Ok(outputs)
     }
 
     pub fn from_weights(
         input_size: usize,
-        output_size: usize,
+        topology: &LayerTopology,
         weights: &mut dyn Iterator<Item = f32>,
     ) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::from_weights(input_size, weights))
-            .collect();
+        Self::try_from_weights(input_size, topology, weights)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_from_weights(
+        input_size: usize,
+        topology: &LayerTopology,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Result<Self, NetworkError> {
+        // Once the total fits, none of the calculations below can overflow
+        let expected = topology
+            .kind
+            .weights_count(input_size, topology.neurons)
+            .ok_or(NetworkError::TopologyTooLarge { layer: 0 })?;
+
+        let neuron_inputs = topology.kind.neuron_inputs(input_size, topology.neurons);
+        let weights_per_neuron = 1 + neuron_inputs;
 
-        Self { neurons }
+        let mut neurons = (0..topology.kind.units(topology.neurons))
+            .map(|neuron| {
+                Neuron::try_from_weights(neuron_inputs, weights).map_err(|err| match err {
+                    NetworkError::NotEnoughWeights { actual, .. } => {
+                        NetworkError::NotEnoughWeights {
+                            layer: 0,
+                            expected,
+                            actual: neuron * weights_per_neuron + actual,
+                        }
+                    }
+                    err => err,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let gates = neurons.split_off(topology.neurons);
+
+        Ok(Self {
+            neurons,
+            activation: topology.activation,
+            kind: topology.kind,
+            gates,
+        })
+    }
+
+    /// Neuron at given index, counting gates after regular neurons (same as
+    /// `weights()` does)
+    crate fn neuron(&self, idx: usize) -> Option<&Neuron> {
+        self.neurons.iter().chain(&self.gates).nth(idx)
+    }
+
+    crate fn neuron_mut(&mut self, idx: usize) -> Option<&mut Neuron> {
+        self.neurons.iter_mut().chain(&mut self.gates).nth(idx)
+    }
+
+    crate fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        use std::iter::once;
+
+        self.neurons
+            .iter_mut()
+            .chain(&mut self.gates)
+            .flat_map(|neuron| once(&mut neuron.bias).chain(&mut neuron.weights))
+    }
+
+    crate fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        use std::iter::once;
+
+        self.neurons
+            .iter()
+            .chain(&self.gates)
+            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
+            .cloned()
     }
 }
@@ -1,18 +1,99 @@
 #![feature(crate_visibility_modifier)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 
 use rand::RngCore;
 
 use crate::layer::Layer;
 
+pub use crate::{
+    activation::Activation,
+    error::NetworkError,
+    initializer::Initializer,
+    layer::LayerKind,
+    matrix::{BatchBuffers, MatrixNetwork},
+    prune::PruneReport,
+    quantized::{AccuracyReport, Quantization, QuantizedNetwork},
+    render::RenderOptions,
+    training::{Adam, Loss, Optimizer, Sgd},
+};
+
+#[cfg(feature = "serde")]
+pub use crate::format::{FormatError, FORMAT_VERSION};
+
+#[cfg(feature = "onnx")]
+pub use crate::onnx::{OnnxError, ONNX_OPSET_VERSION};
+
+#[cfg(feature = "serde")]
+use crate::format::NetworkFile;
+
+mod activation;
+mod arithmetic;
+mod error;
+#[cfg(feature = "serde")]
+mod format;
+mod initializer;
 mod layer;
+mod matrix;
 mod neuron;
-
-#[derive(Debug)]
+#[cfg(feature = "onnx")]
+mod onnx;
+mod prune;
+mod quantized;
+mod render;
+#[cfg(feature = "simd")]
+mod simd;
+mod training;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayerTopology {
     pub neurons: usize,
+
+    /// Activation applied to this layer's neurons; ignored for the input
+    /// layer, since it doesn't have any neurons of its own
+    pub activation: Activation,
+
+    /// Ignored for the input layer, same as `activation`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: LayerKind,
+
+    /// Used only by `Network::random()`, so it's not serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub initializer: Initializer,
 }
 
-#[derive(Debug)]
+impl LayerTopology {
+    pub fn new(neurons: usize) -> Self {
+        Self {
+            neurons,
+            activation: Activation::default(),
+            kind: LayerKind::default(),
+            initializer: Initializer::default(),
+        }
+    }
+
+    pub fn with_activation(self, activation: Activation) -> Self {
+        Self { activation, ..self }
+    }
+
+    pub fn with_kind(self, kind: LayerKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    pub fn with_initializer(self, initializer: Initializer) -> Self {
+        Self {
+            initializer,
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "NetworkFile", try_from = "NetworkFile")
+)]
 pub struct Network {
     layers: Vec<Layer>,
 }
@@ -22,20 +103,65 @@ impl Network {
         layers: &[LayerTopology],
         weights: impl IntoIterator<Item = f32>,
     ) -> Network {
-        assert!(layers.len() > 1);
+        Self::try_from_weights(layers, weights).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = f32>,
+    ) -> Result<Network, NetworkError> {
+        if layers.len() <= 1 {
+            return Err(NetworkError::NotEnoughLayers {
+                actual: layers.len(),
+            });
+        }
+
+        let mut counts = Vec::with_capacity(layers.len() - 1);
+        let mut expected: usize = 0;
+
+        for (layer_idx, layers) in layers.windows(2).enumerate() {
+            let count = layers[1]
+                .kind
+                .weights_count(layers[0].neurons, layers[1].neurons)
+                .and_then(|count| {
+                    expected = expected.checked_add(count)?;
+                    Some(count)
+                })
+                .ok_or(NetworkError::TopologyTooLarge { layer: layer_idx })?;
+
+            counts.push(count);
+        }
 
         let mut weights = weights.into_iter();
+        let mut consumed = 0;
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::from_weights(layers[0].neurons, layers[1].neurons, &mut weights))
-            .collect();
-
+            .enumerate()
+            .map(|(layer_idx, layers)| {
+                let layer = Layer::try_from_weights(layers[0].neurons, &layers[1], &mut weights)
+                    .map_err(|err| match err {
+                        NetworkError::NotEnoughWeights { actual, .. } => {
+                            NetworkError::NotEnoughWeights {
+                                layer: layer_idx,
+                                expected,
+                                actual: consumed + actual,
+                            }
+                        }
+                        err => err.at_layer(layer_idx),
+                    })?;
+
+                consumed += counts[layer_idx];
+                Ok(layer)
+            })
+            .collect::<Result<_, _>>()?;
+
+        // Not counting what's left, as `weights` might never end
         if weights.next().is_some() {
-            panic!("got too many wrights");
+            return Err(NetworkError::TooManyWeights { expected });
         }
 
-        Self { layers }
+        Ok(Self { layers })
     }
 
     pub fn new(layers: Vec<Layer>) -> Self {
@@ -50,26 +176,169 @@ impl Network {
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
+            .map(|layers| Layer::random(rng, layers[0].neurons, &layers[1]))
             .collect();
 
         Self { layers }
     }
 
     pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+        self.try_propagate(inputs)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a fresh, zeroed state for this network's recurrent layers
+    pub fn state(&self) -> NetworkState {
+        NetworkState {
+            layers: self.layers.iter().map(Layer::initial_state).collect(),
+        }
+    }
+
+    /// Like `propagate()`, but recurrent layers see (and then update) what
+    /// they returned during the previous call with the same `state`; plain
+    /// `propagate()` always starts from a fresh state
+    pub fn propagate_with_state(&self, state: &mut NetworkState, inputs: Vec<f32>) -> Vec<f32> {
+        self.try_propagate_with_state(state, inputs)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_propagate_with_state(
+        &self,
+        state: &mut NetworkState,
+        inputs: Vec<f32>,
+    ) -> Result<Vec<f32>, NetworkError> {
+        for layer_idx in 0..self.layers.len().max(state.layers.len()) {
+            let expected = self.layers.get(layer_idx).map_or(0, Layer::state_size);
+            let actual = state.layers.get(layer_idx).map_or(0, Vec::len);
+
+            if expected != actual {
+                return Err(NetworkError::InvalidState {
+                    layer: layer_idx,
+                    expected,
+                    actual,
+                });
+            }
+        }
+
         self.layers
             .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+            .zip(&mut state.layers)
+            .enumerate()
+            .try_fold(inputs, |inputs, (layer_idx, (layer, state))| {
+                layer
+                    .try_propagate_with_state(inputs, state)
+                    .map_err(|err| err.at_layer(layer_idx))
+            })
+    }
+
+    pub fn try_propagate(&self, inputs: Vec<f32>) -> Result<Vec<f32>, NetworkError> {
+        self.layers
+            .iter()
+            .enumerate()
+            .try_fold(inputs, |inputs, (layer_idx, layer)| {
+                layer
+                    .try_propagate(inputs)
+                    .map_err(|err| err.at_layer(layer_idx))
+            })
+    }
+
+    /// Like `propagate()`, but returns outputs of every layer, the last one
+    /// being the network's output; inputs are not included, so that
+    /// `outputs[layer]` lines up with the `layer` index used by `weight()`
+    pub fn propagate_traced(&self, inputs: Vec<f32>) -> Vec<Vec<f32>> {
+        self.try_propagate_traced(inputs)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_propagate_traced(&self, inputs: Vec<f32>) -> Result<Vec<Vec<f32>>, NetworkError> {
+        let mut outputs: Vec<Vec<f32>> = Vec::with_capacity(self.layers.len());
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            let inputs = outputs.last().cloned().unwrap_or_else(|| inputs.clone());
+
+            outputs.push(
+                layer
+                    .try_propagate(inputs)
+                    .map_err(|err| err.at_layer(layer_idx))?,
+            );
+        }
+
+        Ok(outputs)
+    }
+
+    /// Weight connecting `input`-th value going into `layer` with its
+    /// `neuron`-th neuron.
+    ///
+    /// Layers are counted without the input one (the same way
+    /// `NetworkError` counts them). For recurrent layers, inputs past the
+    /// previous layer's size refer to this layer's own outputs from the
+    /// previous step, and neurons past `LayerTopology::neurons` refer to
+    /// update gates.
+    pub fn weight(&self, layer: usize, neuron: usize, input: usize) -> Option<f32> {
+        self.layers
+            .get(layer)?
+            .neuron(neuron)?
+            .weights
+            .get(input)
+            .copied()
+    }
+
+    pub fn weight_mut(&mut self, layer: usize, neuron: usize, input: usize) -> Option<&mut f32> {
+        self.layers
+            .get_mut(layer)?
+            .neuron_mut(neuron)?
+            .weights
+            .get_mut(input)
+    }
+
+    /// Bias of given neuron; indices work the same way as in `weight()`
+    pub fn bias(&self, layer: usize, neuron: usize) -> Option<f32> {
+        Some(self.layers.get(layer)?.neuron(neuron)?.bias)
+    }
+
+    pub fn bias_mut(&mut self, layer: usize, neuron: usize) -> Option<&mut f32> {
+        Some(&mut self.layers.get_mut(layer)?.neuron_mut(neuron)?.bias)
+    }
+
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        let inputs = self.layers.first().map_or(0, Layer::input_size);
+
+        std::iter::once(LayerTopology::new(inputs))
+            .chain(self.layers.iter().map(Layer::topology))
+            .collect()
     }
 
     pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
-        use std::iter::once;
+        self.layers.iter().flat_map(Layer::weights)
+    }
 
+    /// How many of `weights()` belong to each layer, in order
+    pub fn weights_per_layer(&self) -> Vec<usize> {
         self.layers
             .iter()
-            .flat_map(|layer| layer.neurons.iter())
-            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
-            .cloned()
+            .map(|layer| layer.weights().count())
+            .collect()
+    }
+
+    /// Same order as `weights()`
+    crate fn weights_mut(&mut self) -> impl Iterator<Item = &mut f32> + '_ {
+        self.layers.iter_mut().flat_map(Layer::weights_mut)
+    }
+}
+
+/// What recurrent layers of a `Network` returned during the previous step;
+/// create via `Network::state()`
+#[derive(Clone, Debug, Default)]
+pub struct NetworkState {
+    layers: Vec<Vec<f32>>,
+}
+
+impl NetworkState {
+    /// Forgets everything, as if the network has never been run
+    pub fn reset(&mut self) {
+        for layer in &mut self.layers {
+            layer.iter_mut().for_each(|value| *value = 0.0);
+        }
     }
 }
 
@@ -83,10 +352,12 @@ mod tests {
         use rand::SeedableRng;
         use rand_chacha::ChaCha8Rng;
 
+        use super::*;
+
         #[test]
         fn test() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let neuron = Neuron::random(&mut rng, 4);
+            let neuron = Neuron::random(&mut rng, Initializer::default(), 4, 1);
 
             approx::assert_relative_eq!(neuron.bias, -0.6255188);
             approx::assert_relative_eq!(
@@ -94,11 +365,47 @@ mod tests {
                 [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
             );
         }
+
+        #[test]
+        fn test_network() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network =
+                Network::random(&mut rng, &[LayerTopology::new(4), LayerTopology::new(1)]);
+            let actual: Vec<_> = network.weights().collect();
+
+            approx::assert_relative_eq!(
+                actual.as_slice(),
+                [-0.6255188, 0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
+            );
+        }
+
+        #[test]
+        fn test_initializer() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2).with_initializer(Initializer::Constant(0.5)),
+                    LayerTopology::new(1).with_initializer(Initializer::Zeros),
+                ],
+            );
+
+            let actual: Vec<_> = network.weights().collect();
+
+            approx::assert_relative_eq!(
+                actual.as_slice(),
+                [0.5, 0.5, 0.5, 0.5, 0.5, 0.5, 0.0, 0.0, 0.0].as_ref()
+            );
+        }
     }
 
     mod propagate {
         use crate::neuron::Neuron;
 
+        use super::*;
+
         #[test]
         fn test() {
             let neuron = Neuron {
@@ -106,13 +413,52 @@ mod tests {
                 weights: vec![-0.3, 0.8],
             };
 
-            approx::assert_relative_eq!(neuron.propagate(&[-10.0, -10.0]), 0.0);
+            approx::assert_relative_eq!(neuron.propagate(&[-10.0, -10.0], Activation::Relu), 0.0);
 
             approx::assert_relative_eq!(
-                neuron.propagate(&[0.5, 1.0]),
+                neuron.propagate(&[0.5, 1.0], Activation::Relu),
                 (-0.3 * 0.5) + (0.8 * 1.0) + 0.5
             );
         }
+
+        #[test]
+        fn test_activation() {
+            let neuron = Neuron {
+                bias: 0.5,
+                weights: vec![-0.3, 0.8],
+            };
+
+            approx::assert_relative_eq!(
+                neuron.propagate(&[-10.0, -10.0], Activation::Identity),
+                (-0.3 * -10.0) + (0.8 * -10.0) + 0.5
+            );
+
+            approx::assert_relative_eq!(
+                neuron.propagate(&[-10.0, -10.0], Activation::Tanh),
+                -0.99975324
+            );
+        }
+
+        #[test]
+        fn test_network() {
+            let layers = &[
+                LayerTopology::new(2),
+                LayerTopology::new(2),
+                LayerTopology::new(1).with_activation(Activation::Identity),
+            ];
+
+            let network = Network::from_weights(
+                layers,
+                vec![
+                    0.0, 1.0, 0.0, // hidden neuron #1: passes the first input
+                    0.0, 0.0, 1.0, // hidden neuron #2: passes the second input
+                    0.0, -1.0, -1.0, // output neuron: negated sum
+                ],
+            );
+
+            approx::assert_relative_eq!(network.propagate(vec![0.5, 2.0])[0], -2.5);
+            approx::assert_relative_eq!(network.propagate(vec![-0.5, 2.0])[0], -2.0);
+        }
     }
 
     mod weights {
@@ -123,14 +469,140 @@ mod tests {
         #[test]
         fn test() {
             let network = Network::new(vec![
-                Layer::new(vec![Neuron::new(0.1, vec![0.2, 0.3, 0.4])]),
-                Layer::new(vec![Neuron::new(0.5, vec![0.6, 0.7, 0.8])]),
+                Layer::new(
+                    vec![Neuron::new(0.1, vec![0.2, 0.3, 0.4])],
+                    Activation::Relu,
+                ),
+                Layer::new(
+                    vec![Neuron::new(0.5, vec![0.6, 0.7, 0.8])],
+                    Activation::Relu,
+                ),
             ]);
 
             let actual: Vec<_> = network.weights().collect();
             let expected = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
 
             approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+            assert_eq!(network.weights_per_layer(), vec![4, 4]);
+        }
+    }
+
+    mod propagate_traced {
+        use super::*;
+
+        fn network() -> Network {
+            Network::from_weights(
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2),
+                    LayerTopology::new(1).with_activation(Activation::Identity),
+                ],
+                vec![
+                    0.0, 1.0, 0.0, // hidden neuron #1: passes the first input
+                    0.0, 0.0, 1.0, // hidden neuron #2: passes the second input
+                    0.0, -1.0, -1.0, // output neuron: negated sum
+                ],
+            )
+        }
+
+        #[test]
+        fn test() {
+            let actual = network().propagate_traced(vec![-0.5, 2.0]);
+
+            assert_eq!(actual.len(), 2);
+            approx::assert_relative_eq!(actual[0].as_slice(), [0.0, 2.0].as_ref());
+            approx::assert_relative_eq!(actual[1].as_slice(), [-2.0].as_ref());
+        }
+
+        #[test]
+        fn test_matches_propagate() {
+            let network = network();
+            let traced = network.propagate_traced(vec![0.5, 2.0]);
+
+            assert_eq!(traced.last().unwrap(), &network.propagate(vec![0.5, 2.0]));
+        }
+
+        #[test]
+        fn test_invalid_input_size() {
+            let actual = network().try_propagate_traced(vec![1.0]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                NetworkError::InvalidInputSize {
+                    layer: 0,
+                    expected: 2,
+                    actual: 1,
+                }
+            );
+        }
+    }
+
+    mod weight {
+        use super::*;
+
+        fn network() -> Network {
+            Network::from_weights(
+                &[
+                    LayerTopology::new(2),
+                    LayerTopology::new(2),
+                    LayerTopology::new(1),
+                ],
+                vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9],
+            )
+        }
+
+        #[test]
+        fn test() {
+            let network = network();
+
+            assert_eq!(network.weight(0, 0, 0), Some(0.2));
+            assert_eq!(network.weight(0, 1, 1), Some(0.6));
+            assert_eq!(network.weight(1, 0, 1), Some(0.9));
+            assert_eq!(network.bias(0, 1), Some(0.4));
+            assert_eq!(network.bias(1, 0), Some(0.7));
+        }
+
+        #[test]
+        fn test_out_of_bounds() {
+            let network = network();
+
+            assert_eq!(network.weight(2, 0, 0), None);
+            assert_eq!(network.weight(1, 1, 0), None);
+            assert_eq!(network.weight(1, 0, 2), None);
+            assert_eq!(network.bias(0, 2), None);
+        }
+
+        #[test]
+        fn test_mut() {
+            let mut network = network();
+
+            *network.weight_mut(0, 1, 0).unwrap() = -1.0;
+            *network.bias_mut(1, 0).unwrap() = -2.0;
+
+            let actual: Vec<_> = network.weights().collect();
+
+            approx::assert_relative_eq!(
+                actual.as_slice(),
+                [0.1, 0.2, 0.3, 0.4, -1.0, 0.6, -2.0, 0.8, 0.9].as_ref()
+            );
+        }
+
+        #[test]
+        fn test_gates() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(1),
+                    LayerTopology::new(1).with_kind(LayerKind::Gated),
+                ],
+                vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6],
+            );
+
+            // inputs: the previous layer's output, then this layer's own
+            assert_eq!(network.weight(0, 0, 1), Some(0.3));
+
+            // neurons: the candidate, then its update gate
+            assert_eq!(network.bias(0, 1), Some(0.4));
+            assert_eq!(network.weight(0, 1, 0), Some(0.5));
         }
     }
 
@@ -139,7 +611,21 @@ mod tests {
 
         #[test]
         fn test() {
-            let layers = &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 2 }];
+            let layers = &[LayerTopology::new(3), LayerTopology::new(2)];
+
+            let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
+            let network = Network::from_weights(layers, weights.clone());
+            let actual: Vec<_> = network.weights().collect();
+
+            approx::assert_relative_eq!(actual.as_slice(), weights.as_slice());
+        }
+
+        #[test]
+        fn test_activation_does_not_change_weights() {
+            let layers = &[
+                LayerTopology::new(3),
+                LayerTopology::new(2).with_activation(Activation::Sigmoid),
+            ];
 
             let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
             let network = Network::from_weights(layers, weights.clone());
@@ -148,4 +634,323 @@ mod tests {
             approx::assert_relative_eq!(actual.as_slice(), weights.as_slice());
         }
     }
+
+    mod topology {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layers = &[
+                LayerTopology::new(3),
+                LayerTopology::new(2).with_activation(Activation::Tanh),
+                LayerTopology::new(1),
+            ];
+
+            let network = Network::from_weights(layers, vec![0.0; 11]);
+
+            assert_eq!(format!("{:?}", network.topology()), format!("{:?}", layers));
+        }
+    }
+
+    mod try_from_weights {
+        use super::*;
+
+        fn layers() -> [LayerTopology; 3] {
+            [
+                LayerTopology::new(3),
+                LayerTopology::new(2),
+                LayerTopology::new(1),
+            ]
+        }
+
+        #[test]
+        fn test() {
+            let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0, 1.1];
+            let network = Network::try_from_weights(&layers(), weights.clone()).unwrap();
+            let actual: Vec<_> = network.weights().collect();
+
+            approx::assert_relative_eq!(actual.as_slice(), weights.as_slice());
+        }
+
+        #[test]
+        fn test_not_enough_layers() {
+            let actual = Network::try_from_weights(&[LayerTopology::new(3)], vec![]).unwrap_err();
+
+            assert_eq!(actual, NetworkError::NotEnoughLayers { actual: 1 });
+        }
+
+        #[test]
+        fn test_not_enough_weights() {
+            let actual = Network::try_from_weights(&layers(), vec![0.0; 9]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                NetworkError::NotEnoughWeights {
+                    layer: 1,
+                    expected: 11,
+                    actual: 9,
+                }
+            );
+
+            let actual = Network::try_from_weights(&layers(), vec![0.0; 5]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                NetworkError::NotEnoughWeights {
+                    layer: 0,
+                    expected: 11,
+                    actual: 5,
+                }
+            );
+        }
+
+        #[test]
+        fn test_too_many_weights() {
+            let actual = Network::try_from_weights(&layers(), vec![0.0; 14]).unwrap_err();
+
+            assert_eq!(actual, NetworkError::TooManyWeights { expected: 11 });
+        }
+
+        #[test]
+        fn test_topology_too_large() {
+            let actual = Network::try_from_weights(
+                &[LayerTopology::new(usize::MAX), LayerTopology::new(2)],
+                vec![0.0; 4],
+            )
+            .unwrap_err();
+
+            assert_eq!(actual, NetworkError::TopologyTooLarge { layer: 0 });
+
+            // Each layer fits on its own, but not all of them together
+            let half = usize::MAX / 2;
+
+            let actual = Network::try_from_weights(
+                &[
+                    LayerTopology::new(half - 1),
+                    LayerTopology::new(1),
+                    LayerTopology::new(half - 1),
+                ],
+                vec![0.0; 4],
+            )
+            .unwrap_err();
+
+            assert_eq!(actual, NetworkError::TopologyTooLarge { layer: 1 });
+        }
+
+        #[test]
+        fn test_infinite_weights() {
+            let actual = Network::try_from_weights(&layers(), std::iter::repeat(0.0)).unwrap_err();
+
+            assert_eq!(actual, NetworkError::TooManyWeights { expected: 11 });
+        }
+
+        #[test]
+        #[should_panic(expected = "got too many weights")]
+        fn test_panics() {
+            Network::from_weights(&layers(), vec![0.0; 14]);
+        }
+    }
+
+    mod try_propagate {
+        use crate::neuron::Neuron;
+
+        use super::*;
+
+        fn network() -> Network {
+            Network::new(vec![
+                Layer::new(
+                    vec![
+                        Neuron::new(0.0, vec![1.0, 1.0]),
+                        Neuron::new(0.0, vec![1.0, -1.0]),
+                    ],
+                    Activation::Relu,
+                ),
+                Layer::new(vec![Neuron::new(0.0, vec![1.0, 1.0])], Activation::Relu),
+            ])
+        }
+
+        #[test]
+        fn test() {
+            let actual = network().try_propagate(vec![2.0, 1.0]).unwrap();
+
+            approx::assert_relative_eq!(actual.as_slice(), [4.0].as_ref());
+        }
+
+        #[test]
+        fn test_invalid_input_size() {
+            let actual = network().try_propagate(vec![1.0, 2.0, 3.0]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                NetworkError::InvalidInputSize {
+                    layer: 0,
+                    expected: 2,
+                    actual: 3,
+                }
+            );
+        }
+
+        #[test]
+        fn test_invalid_input_size_in_hidden_layer() {
+            let network = Network::new(vec![
+                Layer::new(vec![Neuron::new(0.0, vec![1.0])], Activation::Relu),
+                Layer::new(vec![Neuron::new(0.0, vec![1.0, 1.0])], Activation::Relu),
+            ]);
+
+            let actual = network.try_propagate(vec![1.0]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                NetworkError::InvalidInputSize {
+                    layer: 1,
+                    expected: 2,
+                    actual: 1,
+                }
+            );
+        }
+    }
+
+    mod recurrent {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        use super::*;
+
+        fn elman() -> Network {
+            Network::from_weights(
+                &[
+                    LayerTopology::new(1),
+                    LayerTopology::new(1)
+                        .with_activation(Activation::Identity)
+                        .with_kind(LayerKind::Elman),
+                ],
+                // bias, input weight, recurrent weight
+                vec![0.0, 1.0, 1.0],
+            )
+        }
+
+        #[test]
+        fn test_elman() {
+            let network = elman();
+            let mut state = network.state();
+
+            let actual: Vec<_> = (1..=3)
+                .map(|n| network.propagate_with_state(&mut state, vec![n as f32])[0])
+                .collect();
+
+            approx::assert_relative_eq!(actual.as_slice(), [1.0, 3.0, 6.0].as_ref());
+
+            state.reset();
+
+            approx::assert_relative_eq!(
+                network.propagate_with_state(&mut state, vec![1.0])[0],
+                1.0
+            );
+        }
+
+        #[test]
+        fn test_gated() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(1),
+                    LayerTopology::new(1)
+                        .with_activation(Activation::Identity)
+                        .with_kind(LayerKind::Gated),
+                ],
+                vec![
+                    0.0, 1.0, 0.0, // neuron: passes the input
+                    0.0, 0.0, 0.0, // gate: always half-open
+                ],
+            );
+
+            let mut state = network.state();
+
+            let actual: Vec<_> = (0..3)
+                .map(|_| network.propagate_with_state(&mut state, vec![1.0])[0])
+                .collect();
+
+            approx::assert_relative_eq!(actual.as_slice(), [0.5, 0.75, 0.875].as_ref());
+        }
+
+        #[test]
+        fn test_stateless_propagate() {
+            let network = elman();
+
+            approx::assert_relative_eq!(network.propagate(vec![2.0])[0], 2.0);
+            approx::assert_relative_eq!(network.propagate(vec![2.0])[0], 2.0);
+        }
+
+        #[test]
+        fn test_weights() {
+            let layers = &[
+                LayerTopology::new(2),
+                LayerTopology::new(3)
+                    .with_activation(Activation::Tanh)
+                    .with_kind(LayerKind::Gated),
+                LayerTopology::new(2).with_kind(LayerKind::Elman),
+                LayerTopology::new(1),
+            ];
+
+            // gated: 2 * 3 * (1 + 2 + 3), elman: 2 * (1 + 3 + 2), feed-forward: 1 * (1 + 2)
+            let weights: Vec<_> = (0..51).map(|n| n as f32 / 10.0).collect();
+            let network = Network::from_weights(layers, weights.clone());
+            let actual: Vec<_> = network.weights().collect();
+
+            approx::assert_relative_eq!(actual.as_slice(), weights.as_slice());
+            assert_eq!(format!("{:?}", network.topology()), format!("{:?}", layers));
+
+            assert_eq!(
+                Network::try_from_weights(layers, vec![0.0; 50]).unwrap_err(),
+                NetworkError::NotEnoughWeights {
+                    layer: 2,
+                    expected: 51,
+                    actual: 50,
+                }
+            );
+        }
+
+        #[test]
+        fn test_random() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(4),
+                    LayerTopology::new(3).with_kind(LayerKind::Gated),
+                ],
+            );
+
+            assert_eq!(network.weights().count(), 2 * 3 * (1 + 4 + 3));
+        }
+
+        #[test]
+        fn test_invalid_state() {
+            let network = elman();
+            let mut state = NetworkState::default();
+
+            assert_eq!(
+                network
+                    .try_propagate_with_state(&mut state, vec![1.0])
+                    .unwrap_err(),
+                NetworkError::InvalidState {
+                    layer: 0,
+                    expected: 1,
+                    actual: 0,
+                }
+            );
+        }
+
+        #[test]
+        fn test_invalid_input_size() {
+            assert_eq!(
+                elman().try_propagate(vec![1.0, 2.0]).unwrap_err(),
+                NetworkError::InvalidInputSize {
+                    layer: 0,
+                    expected: 1,
+                    actual: 2,
+                }
+            );
+        }
+    }
 }
@@ -0,0 +1,326 @@
+use crate::{layer::Layer, neuron::dot, Activation, Network, NetworkError};
+
+/// `Network` with each layer's weights laid out as one contiguous, row-major
+/// matrix, for evaluating many inputs at once.
+///
+/// Produces exactly the same outputs as `Network::propagate()` does for the
+/// same weights; recurrent layers aren't supported.
+#[derive(Clone, Debug)]
+pub struct MatrixNetwork {
+    layers: Vec<MatrixLayer>,
+}
+
+#[derive(Clone, Debug)]
+struct MatrixLayer {
+    inputs: usize,
+    outputs: usize,
+    biases: Vec<f32>,
+
+    /// `outputs` rows, `inputs` columns each
+    weights: Vec<f32>,
+
+    activation: Activation,
+}
+
+/// Scratch space for intermediate layers' outputs; keep it around between
+/// calls to `MatrixNetwork::propagate_batch()` to avoid allocating
+#[derive(Clone, Debug, Default)]
+pub struct BatchBuffers {
+    front: Vec<f32>,
+    back: Vec<f32>,
+}
+
+impl BatchBuffers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MatrixNetwork {
+    pub fn new(network: &Network) -> Result<Self, NetworkError> {
+        let mut inputs = network.layers.first().map_or(0, Layer::input_size);
+
+        let layers = network
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(layer_idx, layer)| {
+                if layer.kind.is_recurrent() {
+                    return Err(NetworkError::UnsupportedLayerKind {
+                        layer: layer_idx,
+                        kind: layer.kind,
+                    });
+                }
+
+                let mut weights = Vec::with_capacity(layer.neurons.len() * inputs);
+
+                for neuron in &layer.neurons {
+                    if neuron.weights.len() != inputs {
+                        return Err(NetworkError::InvalidInputSize {
+                            layer: layer_idx,
+                            expected: neuron.weights.len(),
+                            actual: inputs,
+                        });
+                    }
+
+                    weights.extend_from_slice(&neuron.weights);
+                }
+
+                let layer = MatrixLayer {
+                    inputs,
+                    outputs: layer.neurons.len(),
+                    biases: layer.neurons.iter().map(|neuron| neuron.bias).collect(),
+                    weights,
+                    activation: layer.activation,
+                };
+
+                inputs = layer.outputs;
+                Ok(layer)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { layers })
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.layers.first().map_or(0, |layer| layer.inputs)
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.layers.last().map_or(0, |layer| layer.outputs)
+    }
+
+    /// Propagates a batch of inputs laid one after another (so
+    /// `inputs.len()` must be a multiple of `input_size()`), writing outputs
+    /// in the same order into `outputs`
+    pub fn propagate_batch(
+        &self,
+        inputs: &[f32],
+        outputs: &mut [f32],
+        buffers: &mut BatchBuffers,
+    ) -> Result<(), NetworkError> {
+        let input_size = self.input_size();
+        let batch = inputs.len().checked_div(input_size).unwrap_or(0);
+
+        if batch * input_size != inputs.len() {
+            return Err(NetworkError::InvalidInputSize {
+                layer: 0,
+                expected: (batch + 1) * input_size,
+                actual: inputs.len(),
+            });
+        }
+
+        if batch * self.output_size() != outputs.len() {
+            return Err(NetworkError::InvalidOutputSize {
+                expected: batch * self.output_size(),
+                actual: outputs.len(),
+            });
+        }
+
+        let BatchBuffers { front, back } = buffers;
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            let layer_inputs = if layer_idx == 0 {
+                inputs
+            } else {
+                &front[..batch * layer.inputs]
+            };
+
+            if layer_idx + 1 == self.layers.len() {
+                layer.propagate_batch(batch, layer_inputs, outputs);
+            } else {
+                back.resize(batch * layer.outputs, 0.0);
+                layer.propagate_batch(batch, layer_inputs, back);
+                std::mem::swap(front, back);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MatrixLayer {
+    fn propagate_batch(&self, batch: usize, inputs: &[f32], outputs: &mut [f32]) {
+        for sample in 0..batch {
+            let inputs = &inputs[sample * self.inputs..][..self.inputs];
+            let outputs = &mut outputs[sample * self.outputs..][..self.outputs];
+
+            for (neuron, output) in outputs.iter_mut().enumerate() {
+                let weights = &self.weights[neuron * self.inputs..][..self.inputs];
+
+                *output = self
+                    .activation
+                    .apply(dot(inputs, weights) + self.biases[neuron]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::{neuron::Neuron, LayerKind, LayerTopology};
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(5),
+                LayerTopology::new(8),
+                LayerTopology::new(6).with_activation(Activation::Sigmoid),
+                LayerTopology::new(2).with_activation(Activation::Tanh),
+            ],
+        )
+    }
+
+    fn inputs(batch: usize) -> Vec<f32> {
+        let mut rng = ChaCha8Rng::from_seed([1; 32]);
+
+        (0..batch * 5).map(|_| rng.gen_range(-1.0..=1.0)).collect()
+    }
+
+    #[test]
+    fn test_same_as_network() {
+        let network = network();
+        let matrix = MatrixNetwork::new(&network).unwrap();
+        let mut buffers = BatchBuffers::new();
+
+        for &batch in &[1, 3, 40] {
+            let inputs = inputs(batch);
+            let mut actual = vec![0.0; batch * 2];
+
+            matrix
+                .propagate_batch(&inputs, &mut actual, &mut buffers)
+                .unwrap();
+
+            let expected: Vec<_> = inputs
+                .chunks(5)
+                .flat_map(|inputs| network.propagate(inputs.to_vec()))
+                .collect();
+
+            let actual: Vec<_> = actual.iter().map(|output| output.to_bits()).collect();
+            let expected: Vec<_> = expected.iter().map(|output| output.to_bits()).collect();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_single_layer() {
+        let network = Network::new(vec![Layer::new(
+            vec![Neuron::new(0.5, vec![1.0, -1.0])],
+            Activation::Identity,
+        )]);
+
+        let matrix = MatrixNetwork::new(&network).unwrap();
+        let mut outputs = [0.0; 2];
+
+        matrix
+            .propagate_batch(
+                &[1.0, 2.0, 3.0, 1.0],
+                &mut outputs,
+                &mut BatchBuffers::new(),
+            )
+            .unwrap();
+
+        approx::assert_relative_eq!(outputs.as_ref(), [-0.5, 2.5].as_ref());
+    }
+
+    #[test]
+    fn test_reuses_buffers() {
+        let matrix = MatrixNetwork::new(&network()).unwrap();
+        let inputs = inputs(10);
+        let mut outputs = vec![0.0; 20];
+        let mut buffers = BatchBuffers::new();
+
+        matrix
+            .propagate_batch(&inputs, &mut outputs, &mut buffers)
+            .unwrap();
+
+        let front = buffers.front.as_ptr();
+        let back = buffers.back.as_ptr();
+
+        matrix
+            .propagate_batch(&inputs, &mut outputs, &mut buffers)
+            .unwrap();
+
+        assert_eq!(buffers.front.as_ptr(), front);
+        assert_eq!(buffers.back.as_ptr(), back);
+    }
+
+    #[test]
+    fn test_invalid_input_size() {
+        let matrix = MatrixNetwork::new(&network()).unwrap();
+
+        let actual = matrix
+            .propagate_batch(&[0.0; 7], &mut [0.0; 2], &mut BatchBuffers::new())
+            .unwrap_err();
+
+        assert_eq!(
+            actual,
+            NetworkError::InvalidInputSize {
+                layer: 0,
+                expected: 10,
+                actual: 7,
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_output_size() {
+        let matrix = MatrixNetwork::new(&network()).unwrap();
+
+        let actual = matrix
+            .propagate_batch(&[0.0; 10], &mut [0.0; 2], &mut BatchBuffers::new())
+            .unwrap_err();
+
+        assert_eq!(
+            actual,
+            NetworkError::InvalidOutputSize {
+                expected: 4,
+                actual: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_mismatched_layers() {
+        let network = Network::new(vec![
+            Layer::new(vec![Neuron::new(0.0, vec![1.0])], Activation::Relu),
+            Layer::new(vec![Neuron::new(0.0, vec![1.0, 1.0])], Activation::Relu),
+        ]);
+
+        assert_eq!(
+            MatrixNetwork::new(&network).unwrap_err(),
+            NetworkError::InvalidInputSize {
+                layer: 1,
+                expected: 2,
+                actual: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_recurrent_layers() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_kind(LayerKind::Elman),
+            ],
+            vec![0.0; 3],
+        );
+
+        assert_eq!(
+            MatrixNetwork::new(&network).unwrap_err(),
+            NetworkError::UnsupportedLayerKind {
+                layer: 0,
+                kind: LayerKind::Elman,
+            }
+        );
+    }
+}
@@ -1,6 +1,9 @@
-use rand::Rng;
+use rand::RngCore;
 
-#[derive(Debug)]
+use crate::{Activation, Initializer, NetworkError};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Neuron {
     crate bias: f32,
     crate weights: Vec<f32>,
@@ -11,41 +14,90 @@ impl Neuron {
         Self { bias, weights }
     }
 
-    crate fn random(rng: &mut dyn rand::RngCore, output_size: usize) -> Self {
-        let bias = rng.gen_range(-1.0..=1.0);
+    crate fn random(
+        rng: &mut dyn RngCore,
+        initializer: Initializer,
+        fan_in: usize,
+        fan_out: usize,
+    ) -> Self {
+        let bias = initializer.bias(rng);
 
-        let weights = (0..output_size)
-            .map(|_| rng.gen_range(-1.0..=1.0))
+        let weights = (0..fan_in)
+            .map(|_| initializer.weight(rng, fan_in, fan_out))
             .collect();
 
         Self { bias, weights }
     }
 
-    crate fn propagate(&self, inputs: &[f32]) -> f32 {
-        assert_eq!(
-            inputs.len(),
-            self.weights.len(),
-            "got {} inputs, but {} inputs were expected",
-            inputs.len(),
-            self.weights.len()
-        );
-
-        let output: f32 = inputs
-            .iter()
-            .zip(&self.weights)
-            .map(|(input, weight)| input * weight)
-            .sum();
-
-        (output + self.bias).max(0.0)
+    crate fn propagate(&self, inputs: &[f32], activation: Activation) -> f32 {
+        self.try_propagate(inputs, activation)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    crate fn try_propagate(
+        &self,
+        inputs: &[f32],
+        activation: Activation,
+    ) -> Result<f32, NetworkError> {
+        if inputs.len() != self.weights.len() {
+            return Err(NetworkError::InvalidInputSize {
+                layer: 0,
+                expected: self.weights.len(),
+                actual: inputs.len(),
+            });
+        }
+
+        Ok(activation.apply(dot(inputs, &self.weights) + self.bias))
     }
 
     pub fn from_weights(output_neurons: usize, weights: &mut dyn Iterator<Item = f32>) -> Self {
-        let bias = weights.next().expect("got not enough weights");
+        Self::try_from_weights(output_neurons, weights).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_from_weights(
+        output_neurons: usize,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Result<Self, NetworkError> {
+        let expected = 1 + output_neurons;
+        let mut actual = 0;
+
+        let mut next = || {
+            let weight = weights.next().ok_or(NetworkError::NotEnoughWeights {
+                layer: 0,
+                expected,
+                actual,
+            })?;
+
+            actual += 1;
+            Ok(weight)
+        };
+
+        let bias = next()?;
 
         let weights = (0..output_neurons)
-            .map(|_| weights.next().expect("got not enough weights"))
-            .collect();
+            .map(|_| next())
+            .collect::<Result<_, _>>()?;
 
-        Self { bias, weights }
+        Ok(Self { bias, weights })
     }
 }
+
+/// Weighted sum of neuron's inputs; shared by every propagation path, so that
+/// all of them produce exactly the same outputs
+#[cfg(feature = "simd")]
+crate fn dot(inputs: &[f32], weights: &[f32]) -> f32 {
+    crate::simd::dot(inputs, weights)
+}
+
+#[cfg(not(feature = "simd"))]
+crate fn dot(inputs: &[f32], weights: &[f32]) -> f32 {
+    scalar_dot(inputs, weights)
+}
+
+crate fn scalar_dot(inputs: &[f32], weights: &[f32]) -> f32 {
+    inputs
+        .iter()
+        .zip(weights)
+        .map(|(input, weight)| input * weight)
+        .sum()
+}
@@ -0,0 +1,752 @@
+//! ONNX (https://onnx.ai) export and import, so that networks can be run
+//! outside of this crate.
+//!
+//! Each layer becomes a `Gemm` node (`output = input * weight^T + bias`),
+//! followed by a node for its activation (none for `Activation::Identity`).
+//! Importing understands the same subset: a chain of `Gemm`s, each
+//! optionally followed by one of the supported activations.
+
+use std::{collections::HashMap, convert::TryFrom, error::Error, fmt};
+
+use prost::Message;
+
+use self::proto::*;
+use crate::{layer::Layer, neuron::Neuron, Activation, Network, NetworkError};
+
+mod proto;
+
+/// Version of the default ONNX operator set models are exported with
+pub const ONNX_OPSET_VERSION: i64 = 13;
+
+/// IR version matching `ONNX_OPSET_VERSION`
+const IR_VERSION: i64 = 7;
+
+const INPUT_NAME: &str = "input";
+const OUTPUT_NAME: &str = "output";
+
+/// Slope ONNX's `LeakyRelu` uses when the `alpha` attribute is missing
+const DEFAULT_LEAKY_RELU_ALPHA: f32 = 0.01;
+
+impl Network {
+    /// Serializes this network as an ONNX model, taking inputs of shape
+    /// `[batch, input size]` and returning outputs of shape
+    /// `[batch, output size]`.
+    ///
+    /// Recurrent layers and `Activation::Custom` aren't supported.
+    pub fn to_onnx(&self) -> Result<Vec<u8>, OnnxError> {
+        if self.layers.is_empty() {
+            return Err(NetworkError::NotEnoughLayers { actual: 1 }.into());
+        }
+
+        let mut nodes = Vec::new();
+        let mut initializers = Vec::new();
+        let mut input = INPUT_NAME.to_string();
+        let mut input_size = self.layers[0].input_size();
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            if layer.kind.is_recurrent() {
+                return Err(NetworkError::UnsupportedLayerKind {
+                    layer: layer_idx,
+                    kind: layer.kind,
+                }
+                .into());
+            }
+
+            let activation = activation_node(layer.activation)
+                .ok_or(NetworkError::UnsupportedActivation { layer: layer_idx })?;
+
+            let mut weights = Vec::with_capacity(layer.neurons.len() * input_size);
+
+            for neuron in &layer.neurons {
+                if neuron.weights.len() != input_size {
+                    return Err(NetworkError::InvalidInputSize {
+                        layer: layer_idx,
+                        expected: neuron.weights.len(),
+                        actual: input_size,
+                    }
+                    .into());
+                }
+
+                weights.extend_from_slice(&neuron.weights);
+            }
+
+            let output_size = layer.neurons.len();
+            let is_last = layer_idx + 1 == self.layers.len();
+            let weight_name = format!("layer{}.weight", layer_idx);
+            let bias_name = format!("layer{}.bias", layer_idx);
+
+            initializers.push(TensorProto {
+                dims: vec![output_size as i64, input_size as i64],
+                data_type: TensorProto::FLOAT,
+                float_data: weights,
+                name: weight_name.clone(),
+                ..TensorProto::default()
+            });
+
+            initializers.push(TensorProto {
+                dims: vec![output_size as i64],
+                data_type: TensorProto::FLOAT,
+                float_data: layer.neurons.iter().map(|neuron| neuron.bias).collect(),
+                name: bias_name.clone(),
+                ..TensorProto::default()
+            });
+
+            let gemm_output = if is_last && activation.is_none() {
+                OUTPUT_NAME.to_string()
+            } else {
+                format!("layer{}.gemm", layer_idx)
+            };
+
+            nodes.push(NodeProto {
+                input: vec![input, weight_name, bias_name],
+                output: vec![gemm_output.clone()],
+                name: format!("layer{}.gemm", layer_idx),
+                op_type: "Gemm".into(),
+                attribute: vec![AttributeProto::int("transB", 1)],
+            });
+
+            input = gemm_output;
+
+            if let Some((op_type, attribute)) = activation {
+                let name = format!("layer{}.{}", layer_idx, op_type.to_lowercase());
+
+                let output = if is_last {
+                    OUTPUT_NAME.to_string()
+                } else {
+                    name.clone()
+                };
+
+                nodes.push(NodeProto {
+                    input: vec![input],
+                    output: vec![output.clone()],
+                    name,
+                    op_type: op_type.into(),
+                    attribute,
+                });
+
+                input = output;
+            }
+
+            input_size = output_size;
+        }
+
+        let model = ModelProto {
+            ir_version: IR_VERSION,
+            opset_import: vec![OperatorSetIdProto {
+                domain: String::new(),
+                version: ONNX_OPSET_VERSION,
+            }],
+            producer_name: env!("CARGO_PKG_NAME").into(),
+            producer_version: env!("CARGO_PKG_VERSION").into(),
+            graph: Some(GraphProto {
+                node: nodes,
+                name: "network".into(),
+                initializer: initializers,
+                input: vec![value_info(INPUT_NAME, self.layers[0].input_size())],
+                output: vec![value_info(OUTPUT_NAME, input_size)],
+            }),
+        };
+
+        Ok(model.encode_to_vec())
+    }
+
+    /// Reads a network from an ONNX model; see the module's docs for what's
+    /// supported
+    pub fn from_onnx(bytes: &[u8]) -> Result<Self, OnnxError> {
+        let model = ModelProto::decode(bytes)?;
+        let graph = model.graph.ok_or(OnnxError::MissingGraph)?;
+
+        let tensors: HashMap<_, _> = graph
+            .initializer
+            .iter()
+            .map(|tensor| (tensor.name.as_str(), tensor))
+            .collect();
+
+        let mut input = graph
+            .input
+            .iter()
+            .map(|input| input.name.as_str())
+            .find(|name| !tensors.contains_key(name))
+            .ok_or(OnnxError::MissingGraph)?;
+
+        let mut layers = Vec::new();
+        let mut input_size = None;
+        let mut nodes = graph.node.iter().peekable();
+
+        while let Some(node) = nodes.next() {
+            if node.op_type != "Gemm" {
+                return Err(OnnxError::UnsupportedOperator {
+                    node: node.name.clone(),
+                    op_type: node.op_type.clone(),
+                });
+            }
+
+            let (inputs, outputs, weights, biases) = read_gemm(node, input, &tensors)?;
+            let layer_idx = layers.len();
+
+            if let Some(expected) = input_size {
+                if expected != inputs {
+                    return Err(NetworkError::InvalidInputSize {
+                        layer: layer_idx,
+                        expected: inputs,
+                        actual: expected,
+                    }
+                    .into());
+                }
+            }
+
+            input = node.output.first().map_or("", String::as_str);
+
+            let activation = match nodes.peek() {
+                Some(next) if next.op_type != "Gemm" => {
+                    let activation = read_activation(next)?;
+
+                    connect(next, input)?;
+                    input = next.output.first().map_or("", String::as_str);
+                    nodes.next();
+
+                    activation
+                }
+
+                _ => Activation::Identity,
+            };
+
+            let neurons = biases
+                .iter()
+                .enumerate()
+                .map(|(neuron, &bias)| {
+                    Neuron::new(bias, weights[neuron * inputs..][..inputs].to_vec())
+                })
+                .collect();
+
+            layers.push(Layer::new(neurons, activation));
+            input_size = Some(outputs);
+        }
+
+        if layers.is_empty() {
+            return Err(NetworkError::NotEnoughLayers { actual: 1 }.into());
+        }
+
+        Ok(Network::new(layers))
+    }
+}
+
+/// ONNX operator (with attributes) equivalent to given activation; `None`
+/// inside means no node is needed, and `None` outside - that there's no
+/// equivalent
+fn activation_node(activation: Activation) -> Option<Option<(&'static str, Vec<AttributeProto>)>> {
+    Some(match activation {
+        Activation::Relu => Some(("Relu", vec![])),
+
+        Activation::LeakyRelu(slope) => {
+            Some(("LeakyRelu", vec![AttributeProto::float("alpha", slope)]))
+        }
+
+        Activation::Sigmoid => Some(("Sigmoid", vec![])),
+        Activation::Tanh => Some(("Tanh", vec![])),
+        Activation::Identity => None,
+        Activation::Softsign => Some(("Softsign", vec![])),
+        Activation::Custom(_) => return None,
+    })
+}
+
+fn read_activation(node: &NodeProto) -> Result<Activation, OnnxError> {
+    Ok(match node.op_type.as_str() {
+        "Relu" => Activation::Relu,
+
+        "LeakyRelu" => Activation::LeakyRelu(
+            attribute(node, "alpha").map_or(DEFAULT_LEAKY_RELU_ALPHA, |attribute| attribute.f),
+        ),
+
+        "Sigmoid" => Activation::Sigmoid,
+        "Tanh" => Activation::Tanh,
+        "Identity" => Activation::Identity,
+        "Softsign" => Activation::Softsign,
+
+        op_type => {
+            return Err(OnnxError::UnsupportedOperator {
+                node: node.name.clone(),
+                op_type: op_type.into(),
+            });
+        }
+    })
+}
+
+/// Reads `Gemm`'s weights, returning `(inputs, outputs, weights, biases)`
+/// with weights laid out row-major, one row per output
+fn read_gemm(
+    node: &NodeProto,
+    input: &str,
+    tensors: &HashMap<&str, &TensorProto>,
+) -> Result<(usize, usize, Vec<f32>, Vec<f32>), OnnxError> {
+    connect(node, input)?;
+
+    let unsupported = |name: &str| OnnxError::UnsupportedAttribute {
+        node: node.name.clone(),
+        name: name.into(),
+    };
+
+    for attribute in &node.attribute {
+        match attribute.name.as_str() {
+            "alpha" | "beta" if attribute.f == 1.0 => (),
+            "transA" if attribute.i == 0 => (),
+            "transB" => (),
+            name => return Err(unsupported(name)),
+        }
+    }
+
+    let transposed = attribute(node, "transB").map_or(0, |attribute| attribute.i) != 0;
+
+    let tensor = |idx: usize| {
+        let name = node.input.get(idx).map_or("", String::as_str);
+
+        tensors
+            .get(name)
+            .ok_or_else(|| OnnxError::MissingTensor(name.into()))
+    };
+
+    let (weight, weights) = {
+        let tensor = tensor(1)?;
+        (tensor, read_tensor(tensor)?)
+    };
+
+    // `read_tensor()` has already made sure the dimensions aren't negative
+    // and that their product is the number of weights, so they fit in `usize`
+    let (inputs, outputs) = match (weight.dims.as_slice(), transposed) {
+        (&[outputs, inputs], true) | (&[inputs, outputs], false) => {
+            (inputs as usize, outputs as usize)
+        }
+        _ => return Err(OnnxError::InvalidTensor(weight.name.clone())),
+    };
+
+    let bias = tensor(2)?;
+    let biases = read_tensor(bias)?;
+
+    if biases.len() != outputs {
+        return Err(OnnxError::InvalidTensor(bias.name.clone()));
+    }
+
+    let weights = if transposed {
+        weights
+    } else {
+        (0..outputs)
+            .flat_map(|output| (0..inputs).map(move |input| (input, output)))
+            .map(|(input, output)| weights[input * outputs + output])
+            .collect()
+    };
+
+    Ok((inputs, outputs, weights, biases))
+}
+
+fn read_tensor(tensor: &TensorProto) -> Result<Vec<f32>, OnnxError> {
+    if tensor.data_type != TensorProto::FLOAT {
+        return Err(OnnxError::InvalidTensor(tensor.name.clone()));
+    }
+
+    let values: Vec<_> = if tensor.float_data.is_empty() {
+        tensor
+            .raw_data
+            .chunks(4)
+            .map(|bytes| <[u8; 4]>::try_from(bytes).map(f32::from_le_bytes))
+            .collect::<Result<_, _>>()
+            .map_err(|_| OnnxError::InvalidTensor(tensor.name.clone()))?
+    } else {
+        tensor.float_data.clone()
+    };
+
+    let expected = tensor.dims.iter().try_fold(1usize, |expected, &dim| {
+        usize::try_from(dim)
+            .ok()
+            .and_then(|dim| expected.checked_mul(dim))
+    });
+
+    if expected != Some(values.len()) {
+        return Err(OnnxError::InvalidTensor(tensor.name.clone()));
+    }
+
+    Ok(values)
+}
+
+fn attribute<'a>(node: &'a NodeProto, name: &str) -> Option<&'a AttributeProto> {
+    node.attribute
+        .iter()
+        .find(|attribute| attribute.name == name)
+}
+
+/// Makes sure `node` reads the output of the previous node, since only
+/// chains of nodes are supported
+fn connect(node: &NodeProto, input: &str) -> Result<(), OnnxError> {
+    if node.input.first().map(String::as_str) == Some(input) {
+        Ok(())
+    } else {
+        Err(OnnxError::DisconnectedNode(node.name.clone()))
+    }
+}
+
+fn value_info(name: &str, size: usize) -> ValueInfoProto {
+    ValueInfoProto {
+        name: name.into(),
+        value_type: Some(TypeProto {
+            tensor_type: Some(TensorTypeProto {
+                elem_type: TensorProto::FLOAT,
+                shape: Some(TensorShapeProto {
+                    dim: vec![
+                        DimensionProto {
+                            value: Some(Dimension::Param("batch".into())),
+                        },
+                        DimensionProto {
+                            value: Some(Dimension::Value(size as i64)),
+                        },
+                    ],
+                }),
+            }),
+        }),
+    }
+}
+
+#[derive(Debug)]
+pub enum OnnxError {
+    Network(NetworkError),
+    Decode(prost::DecodeError),
+
+    /// Model doesn't have a graph, or the graph doesn't have any inputs
+    MissingGraph,
+
+    UnsupportedOperator {
+        node: String,
+        op_type: String,
+    },
+
+    UnsupportedAttribute {
+        node: String,
+        name: String,
+    },
+
+    /// Node doesn't read the output of the node before it
+    DisconnectedNode(String),
+
+    MissingTensor(String),
+
+    /// Tensor has unsupported type or its shape doesn't match its data
+    InvalidTensor(String),
+}
+
+impl fmt::Display for OnnxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(err) => write!(f, "got invalid network: {}", err),
+            Self::Decode(err) => write!(f, "couldn't decode ONNX model: {}", err),
+            Self::MissingGraph => write!(f, "ONNX model doesn't have a graph with an input"),
+
+            Self::UnsupportedOperator { node, op_type } => write!(
+                f,
+                "node {:?} uses operator {}, which isn't supported",
+                node, op_type
+            ),
+
+            Self::UnsupportedAttribute { node, name } => write!(
+                f,
+                "node {:?} uses attribute {} (or its value), which isn't supported",
+                node, name
+            ),
+
+            Self::DisconnectedNode(node) => write!(
+                f,
+                "node {:?} doesn't read output of the node before it",
+                node
+            ),
+
+            Self::MissingTensor(name) => write!(f, "tensor {:?} doesn't exist", name),
+
+            Self::InvalidTensor(name) => write!(
+                f,
+                "tensor {:?} isn't a float tensor of the expected shape",
+                name
+            ),
+        }
+    }
+}
+
+impl Error for OnnxError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Network(err) => Some(err),
+            Self::Decode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<NetworkError> for OnnxError {
+    fn from(err: NetworkError) -> Self {
+        Self::Network(err)
+    }
+}
+
+impl From<prost::DecodeError> for OnnxError {
+    fn from(err: prost::DecodeError) -> Self {
+        Self::Decode(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::{LayerKind, LayerTopology};
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(5),
+                LayerTopology::new(4).with_activation(Activation::LeakyRelu(0.1)),
+                LayerTopology::new(4).with_activation(Activation::Sigmoid),
+                LayerTopology::new(3).with_activation(Activation::Softsign),
+                LayerTopology::new(3).with_activation(Activation::Tanh),
+                LayerTopology::new(2).with_activation(Activation::Identity),
+            ],
+        )
+    }
+
+    fn samples() -> Vec<Vec<f32>> {
+        let mut rng = ChaCha8Rng::from_seed([1; 32]);
+
+        (0..20)
+            .map(|_| (0..3).map(|_| rng.gen_range(-1.0..=1.0)).collect())
+            .collect()
+    }
+
+    /// Runs an ONNX model on a single input, straight from its graph
+    /// (i.e. without going through `Network::from_onnx()`)
+    fn evaluate(model: &ModelProto, input: &[f32]) -> Vec<f32> {
+        let graph = model.graph.as_ref().unwrap();
+
+        let mut values: HashMap<String, Vec<f32>> = graph
+            .initializer
+            .iter()
+            .map(|tensor| (tensor.name.clone(), read_tensor(tensor).unwrap()))
+            .collect();
+
+        values.insert(graph.input[0].name.clone(), input.to_vec());
+
+        for node in &graph.node {
+            let x = &values[&node.input[0]];
+
+            let y: Vec<f32> = match node.op_type.as_str() {
+                "Gemm" => {
+                    let transposed = attribute(node, "transB").unwrap().i == 1;
+                    let weight = graph
+                        .initializer
+                        .iter()
+                        .find(|tensor| tensor.name == node.input[1])
+                        .unwrap();
+
+                    assert!(transposed);
+
+                    let (outputs, inputs) = (weight.dims[0] as usize, weight.dims[1] as usize);
+                    let weights = &values[&node.input[1]];
+                    let biases = &values[&node.input[2]];
+
+                    (0..outputs)
+                        .map(|output| {
+                            let row = &weights[output * inputs..][..inputs];
+
+                            row.iter().zip(x).map(|(w, x)| w * x).sum::<f32>() + biases[output]
+                        })
+                        .collect()
+                }
+
+                "Relu" => x.iter().map(|x| x.max(0.0)).collect(),
+
+                "LeakyRelu" => {
+                    let alpha = attribute(node, "alpha").unwrap().f;
+
+                    x.iter()
+                        .map(|&x| if x >= 0.0 { x } else { alpha * x })
+                        .collect()
+                }
+
+                "Sigmoid" => x.iter().map(|x| 1.0 / (1.0 + (-x).exp())).collect(),
+                "Tanh" => x.iter().map(|x| x.tanh()).collect(),
+                "Softsign" => x.iter().map(|x| x / (1.0 + x.abs())).collect(),
+                op_type => panic!("unexpected operator: {}", op_type),
+            };
+
+            values.insert(node.output[0].clone(), y);
+        }
+
+        values.remove(&graph.output[0].name).unwrap()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let network = network();
+        let model = ModelProto::decode(network.to_onnx().unwrap().as_slice()).unwrap();
+
+        assert_eq!(model.opset_import[0].version, ONNX_OPSET_VERSION);
+
+        for sample in samples() {
+            approx::assert_relative_eq!(
+                evaluate(&model, &sample).as_slice(),
+                network.propagate(sample).as_slice(),
+                epsilon = 1e-6
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let network = network();
+        let actual = Network::from_onnx(&network.to_onnx().unwrap()).unwrap();
+
+        assert_eq!(
+            actual.weights().collect::<Vec<_>>(),
+            network.weights().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            format!("{:?}", actual.topology()),
+            format!("{:?}", network.topology())
+        );
+
+        for sample in samples() {
+            assert_eq!(actual.propagate(sample.clone()), network.propagate(sample));
+        }
+    }
+
+    #[test]
+    fn test_import_raw_data() {
+        // What e.g. PyTorch would write: weights as raw bytes and laid out
+        // `[inputs, outputs]`, with no activation afterwards
+        let tensor = |name: &str, dims: Vec<i64>, values: &[f32]| TensorProto {
+            dims,
+            data_type: TensorProto::FLOAT,
+            name: name.into(),
+            raw_data: values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            ..TensorProto::default()
+        };
+
+        let model = ModelProto {
+            graph: Some(GraphProto {
+                node: vec![NodeProto {
+                    input: vec!["x".into(), "w".into(), "b".into()],
+                    output: vec!["y".into()],
+                    name: "fc".into(),
+                    op_type: "Gemm".into(),
+                    attribute: vec![AttributeProto::float("alpha", 1.0)],
+                }],
+                initializer: vec![
+                    tensor("w", vec![2, 3], &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]),
+                    tensor("b", vec![3], &[0.1, 0.2, 0.3]),
+                ],
+                input: vec![value_info("x", 2)],
+                output: vec![value_info("y", 3)],
+                ..GraphProto::default()
+            }),
+            ..ModelProto::default()
+        };
+
+        let network = Network::from_onnx(&model.encode_to_vec()).unwrap();
+
+        approx::assert_relative_eq!(
+            network.propagate(vec![1.0, -1.0]).as_slice(),
+            [0.1 - 3.0, 0.2 - 3.0, 0.3 - 3.0].as_ref()
+        );
+    }
+
+    #[test]
+    fn test_invalid_dims() {
+        let model = ModelProto::decode(network().to_onnx().unwrap().as_slice()).unwrap();
+
+        let weights = model
+            .graph
+            .as_ref()
+            .unwrap()
+            .initializer
+            .iter()
+            .position(|tensor| tensor.dims.len() == 2)
+            .unwrap();
+
+        let invalid_dims = |dims: &dyn Fn(&[i64]) -> Vec<i64>| {
+            let mut model = model.clone();
+            let tensor = &mut model.graph.as_mut().unwrap().initializer[weights];
+            let name = tensor.name.clone();
+
+            tensor.dims = dims(&tensor.dims);
+
+            let actual = Network::from_onnx(&model.encode_to_vec()).unwrap_err();
+
+            assert!(
+                matches!(&actual, OnnxError::InvalidTensor(actual) if *actual == name),
+                "{:?}",
+                actual
+            );
+        };
+
+        // Product of these matches the number of weights, so only the signs
+        // give them away
+        invalid_dims(&|dims| dims.iter().map(|dim| -dim).collect());
+
+        invalid_dims(&|_| vec![1 << 32, 1 << 32]);
+        invalid_dims(&|_| vec![i64::MAX, i64::MAX, 0]);
+    }
+
+    #[test]
+    fn test_unsupported_network() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_kind(LayerKind::Elman),
+            ],
+            vec![0.0; 3],
+        );
+
+        assert!(matches!(
+            network.to_onnx(),
+            Err(OnnxError::Network(NetworkError::UnsupportedLayerKind {
+                layer: 0,
+                ..
+            }))
+        ));
+
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_activation(Activation::Custom(f32::cos)),
+            ],
+            vec![0.0; 2],
+        );
+
+        assert!(matches!(
+            network.to_onnx(),
+            Err(OnnxError::Network(NetworkError::UnsupportedActivation {
+                layer: 0
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_model() {
+        let mut model = ModelProto::decode(network().to_onnx().unwrap().as_slice()).unwrap();
+        model.graph.as_mut().unwrap().node[1].op_type = "Elu".into();
+
+        let actual = Network::from_onnx(&model.encode_to_vec()).unwrap_err();
+
+        assert!(
+            matches!(&actual, OnnxError::UnsupportedOperator { op_type, .. } if op_type == "Elu"),
+            "{:?}",
+            actual
+        );
+
+        assert!(matches!(
+            Network::from_onnx(&[0xff, 0xff, 0xff]),
+            Err(OnnxError::Decode(_))
+        ));
+    }
+}
@@ -0,0 +1,177 @@
+//! Subset of `onnx.proto` (https://github.com/onnx/onnx/blob/main/onnx/onnx.proto)
+//! needed to describe feed-forward networks; field tags match the original,
+//! so models written here can be read by any ONNX runtime (and vice versa,
+//! as long as they don't need anything missing here).
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct ModelProto {
+    #[prost(int64, tag = "1")]
+    crate ir_version: i64,
+
+    #[prost(message, repeated, tag = "8")]
+    crate opset_import: Vec<OperatorSetIdProto>,
+
+    #[prost(string, tag = "2")]
+    crate producer_name: String,
+
+    #[prost(string, tag = "3")]
+    crate producer_version: String,
+
+    #[prost(message, optional, tag = "7")]
+    crate graph: Option<GraphProto>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct OperatorSetIdProto {
+    #[prost(string, tag = "1")]
+    crate domain: String,
+
+    #[prost(int64, tag = "2")]
+    crate version: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct GraphProto {
+    #[prost(message, repeated, tag = "1")]
+    crate node: Vec<NodeProto>,
+
+    #[prost(string, tag = "2")]
+    crate name: String,
+
+    #[prost(message, repeated, tag = "5")]
+    crate initializer: Vec<TensorProto>,
+
+    #[prost(message, repeated, tag = "11")]
+    crate input: Vec<ValueInfoProto>,
+
+    #[prost(message, repeated, tag = "12")]
+    crate output: Vec<ValueInfoProto>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct NodeProto {
+    #[prost(string, repeated, tag = "1")]
+    crate input: Vec<String>,
+
+    #[prost(string, repeated, tag = "2")]
+    crate output: Vec<String>,
+
+    #[prost(string, tag = "3")]
+    crate name: String,
+
+    #[prost(string, tag = "4")]
+    crate op_type: String,
+
+    #[prost(message, repeated, tag = "5")]
+    crate attribute: Vec<AttributeProto>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct AttributeProto {
+    #[prost(string, tag = "1")]
+    crate name: String,
+
+    /// `type` in the original
+    #[prost(int32, tag = "20")]
+    crate kind: i32,
+
+    #[prost(float, tag = "2")]
+    crate f: f32,
+
+    #[prost(int64, tag = "3")]
+    crate i: i64,
+}
+
+impl AttributeProto {
+    crate const FLOAT: i32 = 1;
+    crate const INT: i32 = 2;
+
+    crate fn float(name: &str, f: f32) -> Self {
+        Self {
+            name: name.into(),
+            kind: Self::FLOAT,
+            f,
+            ..Self::default()
+        }
+    }
+
+    crate fn int(name: &str, i: i64) -> Self {
+        Self {
+            name: name.into(),
+            kind: Self::INT,
+            i,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct TensorProto {
+    #[prost(int64, repeated, tag = "1")]
+    crate dims: Vec<i64>,
+
+    #[prost(int32, tag = "2")]
+    crate data_type: i32,
+
+    #[prost(float, repeated, tag = "4")]
+    crate float_data: Vec<f32>,
+
+    #[prost(string, tag = "8")]
+    crate name: String,
+
+    /// Little-endian values, used by most exporters instead of `float_data`
+    #[prost(bytes = "vec", tag = "9")]
+    crate raw_data: Vec<u8>,
+}
+
+impl TensorProto {
+    crate const FLOAT: i32 = 1;
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct ValueInfoProto {
+    #[prost(string, tag = "1")]
+    crate name: String,
+
+    /// `type` in the original
+    #[prost(message, optional, tag = "2")]
+    crate value_type: Option<TypeProto>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct TypeProto {
+    #[prost(message, optional, tag = "1")]
+    crate tensor_type: Option<TensorTypeProto>,
+}
+
+/// `TypeProto.Tensor` in the original
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct TensorTypeProto {
+    #[prost(int32, tag = "1")]
+    crate elem_type: i32,
+
+    #[prost(message, optional, tag = "2")]
+    crate shape: Option<TensorShapeProto>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct TensorShapeProto {
+    #[prost(message, repeated, tag = "1")]
+    crate dim: Vec<DimensionProto>,
+}
+
+/// `TensorShapeProto.Dimension` in the original
+#[derive(Clone, PartialEq, prost::Message)]
+crate struct DimensionProto {
+    #[prost(oneof = "Dimension", tags = "1, 2")]
+    crate value: Option<Dimension>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+crate enum Dimension {
+    #[prost(int64, tag = "1")]
+    Value(i64),
+
+    #[prost(string, tag = "2")]
+    Param(String),
+}
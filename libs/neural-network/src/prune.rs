@@ -0,0 +1,328 @@
+use crate::{Network, NetworkError};
+
+/// What `Network::prune()` has removed; all indices refer to the original
+/// network and count layers without the input one (same as `weight()`)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PruneReport {
+    /// Connections whose weight was below the threshold, as
+    /// `(layer, neuron, input)`
+    pub pruned_connections: Vec<(usize, usize, usize)>,
+
+    /// Hidden neurons which were removed, together with all of their
+    /// connections, as `(layer, neuron)`
+    pub removed_neurons: Vec<(usize, usize)>,
+
+    /// Number of weights (biases included) before pruning
+    pub weights_before: usize,
+
+    /// Number of weights (biases included) after pruning
+    pub weights_after: usize,
+
+    /// Largest difference between outputs of the original and the pruned
+    /// network, across all samples
+    pub max_error: f32,
+}
+
+impl Network {
+    /// Creates a smaller network, which behaves (almost) the same as this
+    /// one:
+    ///
+    /// - connections with weights whose magnitude is below `min_weight` get
+    ///   zeroed,
+    ///
+    /// - hidden neurons which return the same value for each of `samples`
+    ///   (e.g. ReLUs that never fire), or which don't have any incoming or
+    ///   outgoing connections left, get removed; whatever constant they were
+    ///   returning is folded into biases of the next layer.
+    ///
+    /// Since dead neurons are detected from `samples`, the pruned network is
+    /// guaranteed to be equivalent only for inputs similar to them - see
+    /// `PruneReport::max_error`. Every neuron is constant over a single
+    /// sample though, so unless there are at least two different samples,
+    /// only neurons without any incoming connections count as constant.
+    /// Each layer keeps at least one neuron.
+    ///
+    /// Recurrent layers aren't supported.
+    pub fn prune(
+        &self,
+        min_weight: f32,
+        samples: &[Vec<f32>],
+    ) -> Result<(Network, PruneReport), NetworkError> {
+        if let Some((layer_idx, layer)) = self
+            .layers
+            .iter()
+            .enumerate()
+            .find(|(_, layer)| layer.kind.is_recurrent())
+        {
+            return Err(NetworkError::UnsupportedLayerKind {
+                layer: layer_idx,
+                kind: layer.kind,
+            });
+        }
+
+        let mut report = PruneReport {
+            weights_before: self.weights().count(),
+            ..PruneReport::default()
+        };
+
+        let mut pruned = self.clone();
+
+        for (layer_idx, layer) in pruned.layers.iter_mut().enumerate() {
+            for (neuron_idx, neuron) in layer.neurons.iter_mut().enumerate() {
+                for (input_idx, weight) in neuron.weights.iter_mut().enumerate() {
+                    if *weight != 0.0 && weight.abs() < min_weight {
+                        *weight = 0.0;
+                        report
+                            .pruned_connections
+                            .push((layer_idx, neuron_idx, input_idx));
+                    }
+                }
+            }
+        }
+
+        // With less than two different samples, every neuron would look
+        // constant
+        let traces_usable = samples.iter().any(|sample| sample != &samples[0]);
+
+        let traces = samples
+            .iter()
+            .map(|sample| pruned.try_propagate_traced(sample.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Neurons are removed back to front, so that indices of the ones yet
+        // to be checked stay the same; removing a neuron doesn't change what
+        // the remaining ones return, so `traces` stay valid too
+        for layer_idx in 0..pruned.layers.len().saturating_sub(1) {
+            for neuron_idx in (0..pruned.layers[layer_idx].neurons.len()).rev() {
+                if pruned.layers[layer_idx].neurons.len() == 1 {
+                    break;
+                }
+
+                let layer = &pruned.layers[layer_idx];
+                let neuron = &layer.neurons[neuron_idx];
+                let next_layer = &pruned.layers[layer_idx + 1];
+
+                let no_inputs = neuron.weights.iter().all(|&weight| weight == 0.0);
+
+                let no_outputs = next_layer
+                    .neurons
+                    .iter()
+                    .all(|next| next.weights[neuron_idx] == 0.0);
+
+                let constant = if no_inputs {
+                    Some(layer.activation.apply(neuron.bias))
+                } else if traces_usable {
+                    let mut outputs = traces.iter().map(|trace| trace[layer_idx][neuron_idx]);
+                    let first = outputs.next();
+
+                    first.filter(|&first| outputs.all(|output| output == first))
+                } else {
+                    None
+                };
+
+                let constant = match (constant, no_outputs) {
+                    (Some(constant), _) => constant,
+                    (None, true) => 0.0,
+                    (None, false) => continue,
+                };
+
+                pruned.layers[layer_idx].neurons.remove(neuron_idx);
+
+                for next in &mut pruned.layers[layer_idx + 1].neurons {
+                    next.bias += constant * next.weights.remove(neuron_idx);
+                }
+
+                report.removed_neurons.push((layer_idx, neuron_idx));
+            }
+        }
+
+        report.removed_neurons.sort_unstable();
+
+        // Connections of removed neurons are accounted for by
+        // `removed_neurons` already
+        let removed = &report.removed_neurons;
+
+        report.pruned_connections.retain(|&(layer, neuron, input)| {
+            !removed.contains(&(layer, neuron))
+                && (layer == 0 || !removed.contains(&(layer - 1, input)))
+        });
+
+        report.weights_after = pruned.weights().count();
+
+        for sample in samples {
+            let expected = self.try_propagate(sample.clone())?;
+            let actual = pruned.try_propagate(sample.clone())?;
+
+            for (expected, actual) in expected.iter().zip(&actual) {
+                report.max_error = report.max_error.max((expected - actual).abs());
+            }
+        }
+
+        Ok((pruned, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Activation, LayerKind, LayerTopology};
+
+    fn samples() -> Vec<Vec<f32>> {
+        vec![
+            vec![0.0, 0.0],
+            vec![1.0, 0.5],
+            vec![0.5, 1.0],
+            vec![1.0, 1.0],
+        ]
+    }
+
+    fn layers(hidden: usize) -> [LayerTopology; 3] {
+        [
+            LayerTopology::new(2),
+            LayerTopology::new(hidden),
+            LayerTopology::new(1).with_activation(Activation::Identity),
+        ]
+    }
+
+    #[test]
+    fn test_pruned_connections() {
+        let network = Network::from_weights(
+            &layers(2),
+            vec![
+                0.0, 1.0, 0.01, // hidden #1
+                0.0, -0.02, 1.0, // hidden #2
+                0.1, 1.0, 2.0, // output
+            ],
+        );
+
+        let (pruned, report) = network.prune(0.05, &samples()).unwrap();
+
+        assert_eq!(report.pruned_connections, vec![(0, 0, 1), (0, 1, 0)]);
+        assert!(report.removed_neurons.is_empty());
+        assert_eq!(report.weights_before, 9);
+        assert_eq!(report.weights_after, 9);
+        approx::assert_relative_eq!(report.max_error, 0.035, epsilon = 1e-6);
+
+        let actual: Vec<_> = pruned.weights().collect();
+
+        approx::assert_relative_eq!(
+            actual.as_slice(),
+            [0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.1, 1.0, 2.0].as_ref()
+        );
+    }
+
+    #[test]
+    fn test_dead_neurons() {
+        let network = Network::from_weights(
+            &layers(3),
+            vec![
+                0.0, 1.0, 1.0, // hidden #1: alive
+                -10.0, 1.0, 1.0, // hidden #2: ReLU which never fires
+                0.0, 1.0, -1.0, // hidden #3: alive
+                0.5, 1.0, 3.0, -1.0, // output
+            ],
+        );
+
+        let (pruned, report) = network.prune(0.0, &samples()).unwrap();
+
+        assert_eq!(report.removed_neurons, vec![(0, 1)]);
+        assert!(report.pruned_connections.is_empty());
+        assert_eq!(report.weights_before, 13);
+        assert_eq!(report.weights_after, 9);
+        approx::assert_relative_eq!(report.max_error, 0.0);
+
+        assert_eq!(
+            format!("{:?}", pruned.topology()),
+            format!("{:?}", layers(2))
+        );
+
+        for sample in samples() {
+            assert_eq!(pruned.propagate(sample.clone()), network.propagate(sample));
+        }
+    }
+
+    #[test]
+    fn test_constants_are_folded() {
+        let network = Network::from_weights(
+            &layers(2),
+            vec![
+                0.5, 0.01, -0.01, // hidden #1: returns 0.5 once pruned
+                0.0, 1.0, 1.0, // hidden #2
+                0.25, 2.0, 1.0, // output
+            ],
+        );
+
+        let (pruned, report) = network.prune(0.05, &[]).unwrap();
+
+        assert_eq!(report.removed_neurons, vec![(0, 0)]);
+        assert!(report.pruned_connections.is_empty());
+
+        let actual: Vec<_> = pruned.weights().collect();
+
+        approx::assert_relative_eq!(
+            actual.as_slice(),
+            [0.0, 1.0, 1.0, 0.25 + 0.5 * 2.0, 1.0].as_ref()
+        );
+    }
+
+    #[test]
+    fn test_single_sample() {
+        let network = Network::from_weights(
+            &layers(2),
+            vec![
+                0.0, 1.0, 1.0, // hidden #1
+                0.0, 1.0, -1.0, // hidden #2
+                0.5, 1.0, 3.0, // output
+            ],
+        );
+
+        for samples in &[vec![vec![1.0, 0.5]], vec![vec![1.0, 0.5]; 3]] {
+            let (pruned, report) = network.prune(0.0, samples).unwrap();
+
+            assert!(report.removed_neurons.is_empty());
+            assert_eq!(pruned.topology()[1].neurons, 2);
+        }
+    }
+
+    #[test]
+    fn test_keeps_one_neuron_per_layer() {
+        let network = Network::from_weights(&layers(2), vec![0.0; 9]);
+        let (pruned, report) = network.prune(0.0, &samples()).unwrap();
+
+        assert_eq!(report.removed_neurons, vec![(0, 1)]);
+        assert_eq!(pruned.topology()[1].neurons, 1);
+    }
+
+    #[test]
+    fn test_recurrent_layers() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_kind(LayerKind::Elman),
+            ],
+            vec![0.0; 3],
+        );
+
+        assert_eq!(
+            network.prune(0.0, &[]).unwrap_err(),
+            NetworkError::UnsupportedLayerKind {
+                layer: 0,
+                kind: LayerKind::Elman,
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_samples() {
+        let network = Network::from_weights(&layers(2), vec![0.0; 9]);
+
+        assert_eq!(
+            network.prune(0.0, &[vec![1.0]]).unwrap_err(),
+            NetworkError::InvalidInputSize {
+                layer: 0,
+                expected: 2,
+                actual: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,588 @@
+use crate::{layer::Layer, Activation, Network, NetworkError};
+
+/// Number of fractional bits in Q16.16 numbers
+const FRACTION_BITS: u32 = 16;
+const ONE: i64 = 1 << FRACTION_BITS;
+
+/// `tanh()` is tabulated over `[0, TANH_RANGE]` (and saturates past it), with
+/// `TANH_STEPS_PER_UNIT` steps per unit, interpolating linearly in between
+const TANH_RANGE: i64 = 8;
+const TANH_STEP_BITS: u32 = 5;
+const TANH_STEPS_PER_UNIT: i64 = 1 << TANH_STEP_BITS;
+
+/// `tanh(i / TANH_STEPS_PER_UNIT)` in Q16.16, for `i` in
+/// `0..=TANH_RANGE * TANH_STEPS_PER_UNIT`; embedded rather than computed, so
+/// that it doesn't depend on the platform's `tanh()` (`test_tanh_table`
+/// checks it against that one though)
+const TANH: [i32; (TANH_RANGE * TANH_STEPS_PER_UNIT) as usize + 1] = [
+    0, 2047, 4091, 6126, 8150, 10157, 12146, 14112, 16051, 17961, 19838, 21681, 23485, 25250,
+    26973, 28652, 30285, 31873, 33412, 34904, 36346, 37740, 39084, 40379, 41625, 42823, 43972,
+    45075, 46131, 47142, 48108, 49031, 49912, 50752, 51552, 52314, 53038, 53727, 54382, 55003,
+    55593, 56152, 56683, 57185, 57660, 58110, 58536, 58939, 59320, 59680, 60019, 60340, 60643,
+    60929, 61199, 61454, 61694, 61920, 62134, 62335, 62524, 62703, 62871, 63029, 63179, 63319,
+    63451, 63576, 63693, 63803, 63907, 64004, 64096, 64182, 64263, 64340, 64412, 64479, 64543,
+    64603, 64659, 64712, 64761, 64808, 64852, 64893, 64932, 64968, 65003, 65035, 65065, 65093,
+    65120, 65145, 65169, 65191, 65212, 65231, 65250, 65267, 65283, 65299, 65313, 65327, 65339,
+    65351, 65362, 65373, 65383, 65392, 65401, 65409, 65417, 65424, 65431, 65437, 65443, 65449,
+    65454, 65459, 65464, 65468, 65472, 65476, 65480, 65483, 65486, 65489, 65492, 65495, 65497,
+    65500, 65502, 65504, 65506, 65508, 65509, 65511, 65512, 65514, 65515, 65516, 65518, 65519,
+    65520, 65521, 65522, 65523, 65523, 65524, 65525, 65526, 65526, 65527, 65527, 65528, 65528,
+    65529, 65529, 65530, 65530, 65530, 65531, 65531, 65531, 65532, 65532, 65532, 65532, 65533,
+    65533, 65533, 65533, 65533, 65534, 65534, 65534, 65534, 65534, 65534, 65534, 65534, 65534,
+    65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535, 65535,
+    65535, 65535, 65535, 65535, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536,
+    65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536,
+    65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536,
+    65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536,
+    65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536, 65536,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quantization {
+    /// 8-bit weights, scaled separately for each layer so that the layer's
+    /// largest weight maps to `127`; biases and activations stay in Q16.16
+    Int8,
+
+    /// Q16.16 fixed point everywhere
+    Fixed,
+}
+
+/// `Network` converted to integers, for inference whose results are the
+/// same on every platform: once created, `propagate()` doesn't use floating
+/// point at all (apart from converting inputs and outputs).
+///
+/// Recurrent layers and `Activation::Custom` aren't supported.
+#[derive(Clone, Debug)]
+pub struct QuantizedNetwork {
+    quantization: Quantization,
+    layers: Vec<QuantizedLayer>,
+}
+
+#[derive(Clone, Debug)]
+struct QuantizedLayer {
+    inputs: usize,
+    outputs: usize,
+
+    /// Q16.16
+    biases: Vec<i32>,
+
+    weights: QuantizedWeights,
+    activation: QuantizedActivation,
+}
+
+#[derive(Clone, Debug)]
+enum QuantizedWeights {
+    /// `outputs` rows, `inputs` columns each; actual weight is
+    /// `weight * multiplier / 2^shift`, which keeps tiny scales as precise
+    /// as large ones
+    Int8 {
+        multiplier: i32,
+        shift: u32,
+        weights: Vec<i8>,
+    },
+
+    /// `outputs` rows, `inputs` columns each, in Q16.16
+    Fixed(Vec<i32>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum QuantizedActivation {
+    Relu,
+
+    /// Slope in Q16.16
+    LeakyRelu(i32),
+
+    Sigmoid,
+    Tanh,
+    Identity,
+    Softsign,
+}
+
+/// How much `QuantizedNetwork` differs from the `Network` it was created
+/// from; see `QuantizedNetwork::accuracy()`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccuracyReport {
+    pub samples: usize,
+
+    /// Largest absolute difference between any two corresponding outputs
+    pub max_error: f32,
+
+    /// Average absolute difference, across all outputs of all samples
+    pub mean_error: f32,
+
+    /// Number of outputs whose sign has changed - e.g. for `lib-simulation`
+    /// this means an animal turning the other way
+    pub sign_mismatches: usize,
+}
+
+impl QuantizedNetwork {
+    pub fn new(network: &Network, quantization: Quantization) -> Result<Self, NetworkError> {
+        let mut inputs = network.layers.first().map_or(0, Layer::input_size);
+
+        let layers = network
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(layer_idx, layer)| {
+                if layer.kind.is_recurrent() {
+                    return Err(NetworkError::UnsupportedLayerKind {
+                        layer: layer_idx,
+                        kind: layer.kind,
+                    });
+                }
+
+                let activation = match layer.activation {
+                    Activation::Relu => QuantizedActivation::Relu,
+                    Activation::LeakyRelu(slope) => QuantizedActivation::LeakyRelu(to_fixed(slope)),
+                    Activation::Sigmoid => QuantizedActivation::Sigmoid,
+                    Activation::Tanh => QuantizedActivation::Tanh,
+                    Activation::Identity => QuantizedActivation::Identity,
+                    Activation::Softsign => QuantizedActivation::Softsign,
+
+                    Activation::Custom(_) => {
+                        return Err(NetworkError::UnsupportedActivation { layer: layer_idx });
+                    }
+                };
+
+                let mut weights = Vec::with_capacity(layer.neurons.len() * inputs);
+
+                for neuron in &layer.neurons {
+                    if neuron.weights.len() != inputs {
+                        return Err(NetworkError::InvalidInputSize {
+                            layer: layer_idx,
+                            expected: neuron.weights.len(),
+                            actual: inputs,
+                        });
+                    }
+
+                    weights.extend_from_slice(&neuron.weights);
+                }
+
+                let weights = match quantization {
+                    Quantization::Int8 => {
+                        let max = weights.iter().fold(0.0f32, |max, w| max.max(w.abs()));
+                        let scale = if max > 0.0 { max as f64 / 127.0 } else { 1.0 };
+                        let (multiplier, shift) = to_multiplier(scale);
+
+                        QuantizedWeights::Int8 {
+                            multiplier,
+                            shift,
+                            weights: weights
+                                .iter()
+                                .map(|&weight| (weight as f64 / scale).round() as i8)
+                                .collect(),
+                        }
+                    }
+
+                    Quantization::Fixed => {
+                        QuantizedWeights::Fixed(weights.iter().copied().map(to_fixed).collect())
+                    }
+                };
+
+                let layer = QuantizedLayer {
+                    inputs,
+                    outputs: layer.neurons.len(),
+                    biases: layer
+                        .neurons
+                        .iter()
+                        .map(|neuron| to_fixed(neuron.bias))
+                        .collect(),
+                    weights,
+                    activation,
+                };
+
+                inputs = layer.outputs;
+                Ok(layer)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            quantization,
+            layers,
+        })
+    }
+
+    pub fn quantization(&self) -> Quantization {
+        self.quantization
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.layers.first().map_or(0, |layer| layer.inputs)
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.layers.last().map_or(0, |layer| layer.outputs)
+    }
+
+    pub fn propagate(&self, inputs: &[f32]) -> Vec<f32> {
+        self.try_propagate(inputs)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_propagate(&self, inputs: &[f32]) -> Result<Vec<f32>, NetworkError> {
+        let inputs: Vec<_> = inputs.iter().copied().map(to_fixed).collect();
+        let outputs = self.try_propagate_fixed(&inputs)?;
+
+        Ok(outputs.into_iter().map(from_fixed).collect())
+    }
+
+    /// Same as `try_propagate()`, but takes and returns Q16.16 numbers
+    pub fn try_propagate_fixed(&self, inputs: &[i32]) -> Result<Vec<i32>, NetworkError> {
+        if inputs.len() != self.input_size() {
+            return Err(NetworkError::InvalidInputSize {
+                layer: 0,
+                expected: self.input_size(),
+                actual: inputs.len(),
+            });
+        }
+
+        Ok(self
+            .layers
+            .iter()
+            .fold(inputs.to_vec(), |inputs, layer| layer.propagate(&inputs)))
+    }
+
+    /// Compares outputs of this network with those of `network` (presumably
+    /// the one this network was created from) on given inputs
+    pub fn accuracy(
+        &self,
+        network: &Network,
+        samples: &[Vec<f32>],
+    ) -> Result<AccuracyReport, NetworkError> {
+        let mut report = AccuracyReport {
+            samples: samples.len(),
+            ..AccuracyReport::default()
+        };
+
+        let mut outputs = 0;
+
+        for sample in samples {
+            let expected = network.try_propagate(sample.clone())?;
+            let actual = self.try_propagate(sample)?;
+
+            for (expected, actual) in expected.iter().zip(&actual) {
+                let error = (expected - actual).abs();
+
+                report.max_error = report.max_error.max(error);
+                report.mean_error += error;
+
+                if (*expected < 0.0) != (*actual < 0.0) {
+                    report.sign_mismatches += 1;
+                }
+
+                outputs += 1;
+            }
+        }
+
+        if outputs > 0 {
+            report.mean_error /= outputs as f32;
+        }
+
+        Ok(report)
+    }
+}
+
+impl QuantizedLayer {
+    fn propagate(&self, inputs: &[i32]) -> Vec<i32> {
+        (0..self.outputs)
+            .map(|neuron| {
+                let row = neuron * self.inputs..(neuron + 1) * self.inputs;
+
+                let sum = match &self.weights {
+                    QuantizedWeights::Int8 {
+                        multiplier,
+                        shift,
+                        weights,
+                    } => {
+                        let sum: i64 = inputs
+                            .iter()
+                            .zip(&weights[row])
+                            .map(|(&input, &weight)| input as i64 * weight as i64)
+                            .sum();
+
+                        saturate_i128((sum as i128 * *multiplier as i128) >> shift)
+                    }
+
+                    QuantizedWeights::Fixed(weights) => {
+                        let sum: i64 = inputs
+                            .iter()
+                            .zip(&weights[row])
+                            .map(|(&input, &weight)| input as i64 * weight as i64)
+                            .sum();
+
+                        sum >> FRACTION_BITS
+                    }
+                };
+
+                let sum = saturate(sum + self.biases[neuron] as i64);
+
+                self.activation.apply(sum)
+            })
+            .collect()
+    }
+}
+
+impl QuantizedActivation {
+    fn apply(&self, x: i32) -> i32 {
+        match *self {
+            Self::Relu => x.max(0),
+
+            Self::LeakyRelu(slope) => {
+                if x > 0 {
+                    x
+                } else {
+                    ((x as i64 * slope as i64) >> FRACTION_BITS) as i32
+                }
+            }
+
+            Self::Sigmoid => (fixed_tanh(x / 2) + ONE as i32) / 2,
+            Self::Tanh => fixed_tanh(x),
+            Self::Identity => x,
+            Self::Softsign => ((x as i64 * ONE) / (ONE + (x as i64).abs())) as i32,
+        }
+    }
+}
+
+fn fixed_tanh(x: i32) -> i32 {
+    let step_bits = FRACTION_BITS - TANH_STEP_BITS;
+    let magnitude = (x as i64).abs();
+    let step = (magnitude >> step_bits) as usize;
+
+    let y = if step + 1 >= TANH.len() {
+        TANH[TANH.len() - 1] as i64
+    } else {
+        let from = TANH[step] as i64;
+        let to = TANH[step + 1] as i64;
+        let fraction = magnitude & ((1 << step_bits) - 1);
+
+        from + (((to - from) * fraction) >> step_bits)
+    };
+
+    (y as i32) * x.signum()
+}
+
+fn saturate(x: i64) -> i32 {
+    x.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+fn saturate_i128(x: i128) -> i64 {
+    x.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// Splits positive `scale` into `multiplier / 2^shift`, with `multiplier` as
+/// large as fits in `i32`; scales too large for that saturate
+fn to_multiplier(scale: f64) -> (i32, u32) {
+    let mut scale = scale;
+    let mut shift = 0;
+
+    // Doubling is exact, so this doesn't lose any precision
+    while scale < (1u64 << 30) as f64 && shift < 127 {
+        scale *= 2.0;
+        shift += 1;
+    }
+
+    let multiplier = (scale.round() as i64).min(i32::MAX as i64) as i32;
+
+    (multiplier, shift)
+}
+
+fn to_fixed(x: f32) -> i32 {
+    saturate((x as f64 * ONE as f64).round() as i64)
+}
+
+fn from_fixed(x: i32) -> f32 {
+    (x as f64 / ONE as f64) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::{LayerKind, LayerTopology};
+
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(5),
+                LayerTopology::new(8),
+                LayerTopology::new(6).with_activation(Activation::Sigmoid),
+                LayerTopology::new(4).with_activation(Activation::Softsign),
+                LayerTopology::new(3).with_activation(Activation::LeakyRelu(0.1)),
+                LayerTopology::new(2).with_activation(Activation::Tanh),
+            ],
+        )
+    }
+
+    fn samples() -> Vec<Vec<f32>> {
+        let mut rng = ChaCha8Rng::from_seed([1; 32]);
+
+        (0..100)
+            .map(|_| (0..5).map(|_| rng.gen_range(-1.0..=1.0)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_fixed() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(1).with_activation(Activation::Identity),
+            ],
+            vec![0.25, 0.5, -1.5],
+        );
+
+        let quantized = QuantizedNetwork::new(&network, Quantization::Fixed).unwrap();
+
+        assert_eq!(quantized.propagate(&[2.0, 1.0]), vec![-0.25]);
+
+        assert_eq!(
+            quantized
+                .try_propagate_fixed(&[2 * ONE as i32, ONE as i32])
+                .unwrap(),
+            vec![-(ONE as i32) / 4]
+        );
+    }
+
+    #[test]
+    fn test_int8() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(1).with_activation(Activation::Identity),
+            ],
+            vec![0.25, 1.27, -0.6],
+        );
+
+        let quantized = QuantizedNetwork::new(&network, Quantization::Int8).unwrap();
+
+        // scale is 0.01, so weights become 127 and -60
+        approx::assert_relative_eq!(
+            quantized.propagate(&[1.0, 1.0])[0],
+            0.25 + 1.27 - 0.6,
+            epsilon = 1e-4
+        );
+    }
+
+    #[test]
+    fn test_int8_tiny_weights() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(1).with_activation(Activation::Identity),
+            ],
+            vec![0.0, 1e-4, -5e-5],
+        );
+
+        let quantized = QuantizedNetwork::new(&network, Quantization::Int8).unwrap();
+
+        approx::assert_relative_eq!(
+            quantized.propagate(&[1000.0, 1000.0])[0],
+            0.05,
+            epsilon = 1e-3
+        );
+    }
+
+    #[test]
+    fn test_tanh_table() {
+        for (step, &actual) in TANH.iter().enumerate() {
+            let expected = (step as f64 / TANH_STEPS_PER_UNIT as f64).tanh();
+
+            assert_eq!(actual, (expected * ONE as f64).round() as i32);
+        }
+    }
+
+    #[test]
+    fn test_accuracy() {
+        let network = network();
+        let samples = samples();
+
+        let fixed = QuantizedNetwork::new(&network, Quantization::Fixed)
+            .unwrap()
+            .accuracy(&network, &samples)
+            .unwrap();
+
+        let int8 = QuantizedNetwork::new(&network, Quantization::Int8)
+            .unwrap()
+            .accuracy(&network, &samples)
+            .unwrap();
+
+        assert_eq!(fixed.samples, 100);
+        assert!(fixed.max_error < 1e-3, "{:?}", fixed);
+        assert!(int8.max_error < 5e-2, "{:?}", int8);
+        assert!(fixed.mean_error <= fixed.max_error);
+        assert!(int8.mean_error <= int8.max_error);
+    }
+
+    #[test]
+    fn test_activations() {
+        for &x in &[-10.0, -3.0, -0.7, -0.01, 0.0, 0.3, 1.0, 2.5, 7.99, 100.0] {
+            for &(activation, quantized) in &[
+                (Activation::Relu, QuantizedActivation::Relu),
+                (
+                    Activation::LeakyRelu(0.2),
+                    QuantizedActivation::LeakyRelu(to_fixed(0.2)),
+                ),
+                (Activation::Sigmoid, QuantizedActivation::Sigmoid),
+                (Activation::Tanh, QuantizedActivation::Tanh),
+                (Activation::Identity, QuantizedActivation::Identity),
+                (Activation::Softsign, QuantizedActivation::Softsign),
+            ] {
+                let actual = from_fixed(quantized.apply(to_fixed(x)));
+
+                approx::assert_relative_eq!(actual, activation.apply(x), epsilon = 2e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_kind(LayerKind::Elman),
+            ],
+            vec![0.0; 5],
+        );
+
+        assert_eq!(
+            QuantizedNetwork::new(&network, Quantization::Fixed).unwrap_err(),
+            NetworkError::UnsupportedLayerKind {
+                layer: 1,
+                kind: LayerKind::Elman,
+            }
+        );
+
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_activation(Activation::Custom(f32::cos)),
+            ],
+            vec![0.0; 2],
+        );
+
+        assert_eq!(
+            QuantizedNetwork::new(&network, Quantization::Int8).unwrap_err(),
+            NetworkError::UnsupportedActivation { layer: 0 }
+        );
+    }
+
+    #[test]
+    fn test_invalid_input_size() {
+        let quantized = QuantizedNetwork::new(&network(), Quantization::Fixed).unwrap();
+
+        assert_eq!(
+            quantized.try_propagate(&[1.0]).unwrap_err(),
+            NetworkError::InvalidInputSize {
+                layer: 0,
+                expected: 5,
+                actual: 1,
+            }
+        );
+    }
+}
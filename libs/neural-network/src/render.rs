@@ -0,0 +1,405 @@
+//! Renders networks to Graphviz' DOT and to standalone SVG.
+//!
+//! Connections are coloured by their weight's sign (green for positive, red
+//! for negative) and get thicker as the weight's magnitude grows. Update
+//! gates of `LayerKind::Gated` layers are not drawn, only the neurons
+//! themselves; recurrent connections are drawn dashed.
+
+use std::fmt::Write;
+
+use crate::Network;
+
+const POSITIVE_COLOR: &str = "#2f855a";
+const NEGATIVE_COLOR: &str = "#c53030";
+
+const MIN_PEN_WIDTH: f32 = 0.5;
+const MAX_PEN_WIDTH: f32 = 4.0;
+
+const SVG_LAYER_SPACING: f32 = 160.0;
+const SVG_NEURON_SPACING: f32 = 40.0;
+const SVG_NEURON_RADIUS: f32 = 12.0;
+const SVG_LABEL_MARGIN: f32 = 100.0;
+
+#[derive(Clone, Debug, Default)]
+pub struct RenderOptions {
+    /// Connections whose weight's magnitude is below this are not drawn
+    pub min_weight: f32,
+
+    /// Labels of input neurons; neurons without a label are named after their
+    /// index
+    pub input_labels: Vec<String>,
+
+    /// Labels of output neurons; neurons without a label are named after their
+    /// index
+    pub output_labels: Vec<String>,
+}
+
+impl RenderOptions {
+    pub fn with_min_weight(self, min_weight: f32) -> Self {
+        Self { min_weight, ..self }
+    }
+
+    pub fn with_input_labels(self, labels: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            input_labels: labels.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    pub fn with_output_labels(self, labels: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            output_labels: labels.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+}
+
+/// A connection about to get drawn; `from` and `to` are `(layer, neuron)`,
+/// where layer 0 is the input one
+struct Edge {
+    from: (usize, usize),
+    to: (usize, usize),
+    weight: f32,
+    recurrent: bool,
+}
+
+impl Edge {
+    fn color(&self) -> &'static str {
+        if self.weight >= 0.0 {
+            POSITIVE_COLOR
+        } else {
+            NEGATIVE_COLOR
+        }
+    }
+
+    fn pen_width(&self, max_weight: f32) -> f32 {
+        let magnitude = if max_weight > 0.0 {
+            self.weight.abs() / max_weight
+        } else {
+            0.0
+        };
+
+        MIN_PEN_WIDTH + (MAX_PEN_WIDTH - MIN_PEN_WIDTH) * magnitude
+    }
+}
+
+impl Network {
+    pub fn to_dot(&self, options: &RenderOptions) -> String {
+        let sizes = self.layer_sizes();
+        let edges = self.edges(options);
+        let max_weight = max_weight(&edges);
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph network {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+        writeln!(dot, "    node [shape=circle];").unwrap();
+
+        for (layer, &size) in sizes.iter().enumerate() {
+            writeln!(dot).unwrap();
+            writeln!(dot, "    subgraph layer_{} {{", layer).unwrap();
+            writeln!(dot, "        rank=same;").unwrap();
+
+            for neuron in 0..size {
+                writeln!(
+                    dot,
+                    "        n{}_{} [label=\"{}\"];",
+                    layer,
+                    neuron,
+                    escape_dot(&label(options, &sizes, layer, neuron)),
+                )
+                .unwrap();
+            }
+
+            writeln!(dot, "    }}").unwrap();
+        }
+
+        if !edges.is_empty() {
+            writeln!(dot).unwrap();
+        }
+
+        for edge in &edges {
+            write!(
+                dot,
+                "    n{}_{} -> n{}_{} [color=\"{}\", penwidth={:.2}, tooltip=\"{}\"",
+                edge.from.0,
+                edge.from.1,
+                edge.to.0,
+                edge.to.1,
+                edge.color(),
+                edge.pen_width(max_weight),
+                edge.weight,
+            )
+            .unwrap();
+
+            if edge.recurrent {
+                write!(dot, ", style=dashed, constraint=false").unwrap();
+            }
+
+            writeln!(dot, "];").unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    pub fn to_svg(&self, options: &RenderOptions) -> String {
+        let sizes = self.layer_sizes();
+        let edges = self.edges(options);
+        let max_weight = max_weight(&edges);
+        let tallest = sizes.iter().copied().max().unwrap_or(0) as f32;
+
+        let width = 2.0 * SVG_LABEL_MARGIN + (sizes.len().max(1) - 1) as f32 * SVG_LAYER_SPACING;
+        let height = (tallest + 1.0) * SVG_NEURON_SPACING;
+
+        let position = |(layer, neuron): (usize, usize)| {
+            let x = SVG_LABEL_MARGIN + layer as f32 * SVG_LAYER_SPACING;
+            let top = (tallest - sizes[layer] as f32) / 2.0;
+            let y = (top + neuron as f32 + 1.0) * SVG_NEURON_SPACING;
+
+            (x, y)
+        };
+
+        let mut svg = String::new();
+
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = width,
+            h = height,
+        )
+        .unwrap();
+
+        for edge in &edges {
+            let (x1, y1) = position(edge.from);
+            let (x2, y2) = position(edge.to);
+
+            let shape = if edge.recurrent {
+                // Loops back from this layer to itself, bulging to the right
+                let bulge = SVG_LAYER_SPACING / 3.0;
+
+                format!(
+                    "<path d=\"M {} {} C {} {}, {} {}, {} {}\" fill=\"none\" stroke-dasharray=\"4 2\"",
+                    x1,
+                    y1,
+                    x1 + bulge,
+                    y1,
+                    x2 + bulge,
+                    y2,
+                    x2,
+                    y2,
+                )
+            } else {
+                format!(
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"",
+                    x1, y1, x2, y2
+                )
+            };
+
+            writeln!(
+                svg,
+                "  {} stroke=\"{}\" stroke-width=\"{:.2}\"><title>{}</title></{}>",
+                shape,
+                edge.color(),
+                edge.pen_width(max_weight),
+                edge.weight,
+                if edge.recurrent { "path" } else { "line" },
+            )
+            .unwrap();
+        }
+
+        let last_layer = sizes.len() - 1;
+
+        for (layer, &size) in sizes.iter().enumerate() {
+            for neuron in 0..size {
+                let (x, y) = position((layer, neuron));
+                let label = escape_xml(&label(options, &sizes, layer, neuron));
+
+                writeln!(
+                    svg,
+                    "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"white\" stroke=\"black\"><title>{}</title></circle>",
+                    x, y, SVG_NEURON_RADIUS, label,
+                )
+                .unwrap();
+
+                // Hidden neurons have nowhere to put their labels without
+                // covering connections, so they get only the tooltip
+                let anchor = if layer == 0 {
+                    Some(("end", x - SVG_NEURON_RADIUS - 4.0))
+                } else if layer == last_layer {
+                    Some(("start", x + SVG_NEURON_RADIUS + 4.0))
+                } else {
+                    None
+                };
+
+                if let Some((anchor, x)) = anchor {
+                    writeln!(
+                        svg,
+                        "  <text x=\"{}\" y=\"{}\" text-anchor=\"{}\" dominant-baseline=\"middle\" font-family=\"sans-serif\" font-size=\"12\">{}</text>",
+                        x, y, anchor, label,
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        writeln!(svg, "</svg>").unwrap();
+        svg
+    }
+
+    /// Number of neurons in each layer, the input one included
+    fn layer_sizes(&self) -> Vec<usize> {
+        self.topology().iter().map(|layer| layer.neurons).collect()
+    }
+
+    fn edges(&self, options: &RenderOptions) -> Vec<Edge> {
+        let mut edges = Vec::new();
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            let input_size = layer.input_size();
+
+            for (neuron_idx, neuron) in layer.neurons.iter().enumerate() {
+                for (input_idx, &weight) in neuron.weights.iter().enumerate() {
+                    if weight.abs() < options.min_weight {
+                        continue;
+                    }
+
+                    let (from, recurrent) = if input_idx < input_size {
+                        ((layer_idx, input_idx), false)
+                    } else {
+                        ((layer_idx + 1, input_idx - input_size), true)
+                    };
+
+                    edges.push(Edge {
+                        from,
+                        to: (layer_idx + 1, neuron_idx),
+                        weight,
+                        recurrent,
+                    });
+                }
+            }
+        }
+
+        edges
+    }
+}
+
+fn max_weight(edges: &[Edge]) -> f32 {
+    edges
+        .iter()
+        .map(|edge| edge.weight.abs())
+        .fold(0.0, f32::max)
+}
+
+fn label(options: &RenderOptions, sizes: &[usize], layer: usize, neuron: usize) -> String {
+    let labels = if layer == 0 {
+        &options.input_labels
+    } else if layer == sizes.len() - 1 {
+        &options.output_labels
+    } else {
+        return format!("{}", neuron);
+    };
+
+    labels
+        .get(neuron)
+        .cloned()
+        .unwrap_or_else(|| format!("{}", neuron))
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LayerKind, LayerTopology};
+
+    fn network() -> Network {
+        Network::from_weights(
+            &[LayerTopology::new(2), LayerTopology::new(1)],
+            vec![0.0, 2.0, -0.5],
+        )
+    }
+
+    #[test]
+    fn test_dot() {
+        let options = RenderOptions::default()
+            .with_input_labels(vec!["eye 0", "eye \"1\""])
+            .with_output_labels(vec!["speed"]);
+
+        let actual = network().to_dot(&options);
+
+        let expected = r##"digraph network {
+    rankdir=LR;
+    node [shape=circle];
+
+    subgraph layer_0 {
+        rank=same;
+        n0_0 [label="eye 0"];
+        n0_1 [label="eye \"1\""];
+    }
+
+    subgraph layer_1 {
+        rank=same;
+        n1_0 [label="speed"];
+    }
+
+    n0_0 -> n1_0 [color="#2f855a", penwidth=4.00, tooltip="2"];
+    n0_1 -> n1_0 [color="#c53030", penwidth=1.38, tooltip="-0.5"];
+}
+"##;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_dot_min_weight() {
+        let actual = network().to_dot(&RenderOptions::default().with_min_weight(1.0));
+
+        assert!(actual.contains("n0_0 -> n1_0"));
+        assert!(!actual.contains("n0_1 -> n1_0"));
+        assert!(actual.contains("n0_1 [label=\"1\"]"));
+    }
+
+    #[test]
+    fn test_dot_recurrent() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_kind(LayerKind::Elman),
+            ],
+            vec![0.0, 1.0, -1.0],
+        );
+
+        let actual = network.to_dot(&RenderOptions::default());
+
+        assert!(actual.contains("n0_0 -> n1_0 [color=\"#2f855a\", penwidth=4.00, tooltip=\"1\"];"));
+        assert!(actual.contains(
+            "n1_0 -> n1_0 [color=\"#c53030\", penwidth=4.00, tooltip=\"-1\", style=dashed, constraint=false];"
+        ));
+    }
+
+    #[test]
+    fn test_svg() {
+        let options = RenderOptions::default()
+            .with_input_labels(vec!["<eye>"])
+            .with_output_labels(vec!["speed"]);
+
+        let actual = network().to_svg(&options);
+
+        assert!(actual.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(actual.ends_with("</svg>\n"));
+        assert_eq!(actual.matches("<circle").count(), 3);
+        assert_eq!(actual.matches("<line").count(), 2);
+        assert!(actual.contains("&lt;eye&gt;"));
+        assert!(actual.contains(">speed</text>"));
+        assert!(actual.contains("stroke=\"#c53030\" stroke-width=\"1.38\""));
+    }
+}
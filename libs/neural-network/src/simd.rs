@@ -0,0 +1,89 @@
+//! Vectorized `dot()`, enabled by the `simd` feature.
+//!
+//! On wasm32 built with `simd128` (e.g. `RUSTFLAGS="-C
+//! target-feature=+simd128"`) this uses wasm's intrinsics directly;
+//! everywhere else it goes through `std::simd`, which picks whatever the
+//! target supports (falling back to scalar code if it's nothing).
+//!
+//! Adding lanes separately changes the order of additions, so results can
+//! differ from the scalar version in the last few bits - but all the
+//! propagation paths share `dot()`, so they still agree with each other.
+
+use crate::neuron::scalar_dot;
+
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+crate fn dot(inputs: &[f32], weights: &[f32]) -> f32 {
+    use std::simd::{f32x8, num::SimdFloat};
+
+    const LANES: usize = 8;
+
+    let len = inputs.len().min(weights.len());
+    let (inputs, weights) = (&inputs[..len], &weights[..len]);
+    let tail = len - len % LANES;
+    let mut sum = f32x8::splat(0.0);
+
+    for i in (0..tail).step_by(LANES) {
+        sum += f32x8::from_slice(&inputs[i..]) * f32x8::from_slice(&weights[i..]);
+    }
+
+    sum.reduce_sum() + scalar_dot(&inputs[tail..], &weights[tail..])
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+crate fn dot(inputs: &[f32], weights: &[f32]) -> f32 {
+    use std::arch::wasm32::*;
+
+    const LANES: usize = 4;
+
+    let len = inputs.len().min(weights.len());
+    let (inputs, weights) = (&inputs[..len], &weights[..len]);
+    let tail = len - len % LANES;
+    let mut sum = f32x4_splat(0.0);
+
+    for i in (0..tail).step_by(LANES) {
+        // Safety: `i + LANES <= len`, so both loads stay within the slices;
+        // wasm doesn't require them to be aligned
+        let (inputs, weights) = unsafe {
+            (
+                v128_load(inputs.as_ptr().add(i) as *const v128),
+                v128_load(weights.as_ptr().add(i) as *const v128),
+            )
+        };
+
+        sum = f32x4_add(sum, f32x4_mul(inputs, weights));
+    }
+
+    f32x4_extract_lane::<0>(sum)
+        + f32x4_extract_lane::<1>(sum)
+        + f32x4_extract_lane::<2>(sum)
+        + f32x4_extract_lane::<3>(sum)
+        + scalar_dot(&inputs[tail..], &weights[tail..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_as_scalar() {
+        for len in 0..40 {
+            let inputs: Vec<_> = (0..len).map(|i| (i as f32 * 0.37).sin()).collect();
+            let weights: Vec<_> = (0..len).map(|i| (i as f32 * 0.73).cos()).collect();
+
+            approx::assert_relative_eq!(
+                dot(&inputs, &weights),
+                scalar_dot(&inputs, &weights),
+                epsilon = 1e-5
+            );
+        }
+    }
+
+    #[test]
+    fn test_different_lengths() {
+        let inputs = [1.0; 11];
+        let weights = [2.0; 9];
+
+        approx::assert_relative_eq!(dot(&inputs, &weights), 18.0);
+        approx::assert_relative_eq!(dot(&weights, &inputs), 18.0);
+    }
+}
@@ -0,0 +1,541 @@
+use crate::{neuron::dot, Network, NetworkError};
+
+/// Clamps outputs given to `Loss::CrossEntropy`, so that it never has to
+/// compute `ln(0)`
+const CROSS_ENTROPY_EPSILON: f32 = 1e-7;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Loss {
+    /// `mean((output - target)^2)`
+    MeanSquaredError,
+
+    /// Binary cross-entropy,
+    /// `mean(-target * ln(output) - (1 - target) * ln(1 - output))`; expects
+    /// outputs within `(0, 1)`, e.g. coming from `Activation::Sigmoid`
+    CrossEntropy,
+}
+
+impl Loss {
+    pub fn loss(&self, outputs: &[f32], targets: &[f32]) -> f32 {
+        let sum: f32 = outputs
+            .iter()
+            .zip(targets)
+            .map(|(&output, &target)| match self {
+                Self::MeanSquaredError => (output - target).powi(2),
+
+                Self::CrossEntropy => {
+                    let output = output.clamp(CROSS_ENTROPY_EPSILON, 1.0 - CROSS_ENTROPY_EPSILON);
+                    -target * output.ln() - (1.0 - target) * (1.0 - output).ln()
+                }
+            })
+            .sum();
+
+        sum / outputs.len().max(1) as f32
+    }
+
+    /// Derivative of `loss()` with respect to each output
+    pub fn gradient(&self, outputs: &[f32], targets: &[f32]) -> Vec<f32> {
+        let len = outputs.len().max(1) as f32;
+
+        outputs
+            .iter()
+            .zip(targets)
+            .map(|(&output, &target)| match self {
+                Self::MeanSquaredError => 2.0 * (output - target) / len,
+
+                Self::CrossEntropy => {
+                    let output = output.clamp(CROSS_ENTROPY_EPSILON, 1.0 - CROSS_ENTROPY_EPSILON);
+                    (output - target) / (output * (1.0 - output)) / len
+                }
+            })
+            .collect()
+    }
+}
+
+pub trait Optimizer {
+    /// Updates `weights` (given in the same order as `Network::weights()`
+    /// returns them), knowing the gradient of loss with respect to each of
+    /// them
+    fn step(&mut self, weights: &mut [f32], gradients: &[f32]);
+}
+
+/// Stochastic gradient descent, with optional momentum
+#[derive(Clone, Debug)]
+pub struct Sgd {
+    learning_rate: f32,
+    momentum: f32,
+    velocity: Vec<f32>,
+}
+
+impl Sgd {
+    pub fn new(learning_rate: f32) -> Self {
+        Self::with_momentum(learning_rate, 0.0)
+    }
+
+    pub fn with_momentum(learning_rate: f32, momentum: f32) -> Self {
+        assert!((0.0..1.0).contains(&momentum));
+
+        Self {
+            learning_rate,
+            momentum,
+            velocity: Vec::new(),
+        }
+    }
+}
+
+impl Optimizer for Sgd {
+    fn step(&mut self, weights: &mut [f32], gradients: &[f32]) {
+        self.velocity.resize(weights.len(), 0.0);
+
+        for ((weight, gradient), velocity) in
+            weights.iter_mut().zip(gradients).zip(&mut self.velocity)
+        {
+            *velocity = self.momentum * *velocity - self.learning_rate * gradient;
+            *weight += *velocity;
+        }
+    }
+}
+
+/// Adam, as described in "Adam: A Method for Stochastic Optimization"
+#[derive(Clone, Debug)]
+pub struct Adam {
+    learning_rate: f32,
+    beta1: f32,
+    beta2: f32,
+    epsilon: f32,
+    steps: i32,
+    means: Vec<f32>,
+    variances: Vec<f32>,
+}
+
+impl Adam {
+    /// Creates Adam with the usual defaults: `beta1 = 0.9`, `beta2 = 0.999`
+    /// and `epsilon = 1e-8`
+    pub fn new(learning_rate: f32) -> Self {
+        Self::with_betas(learning_rate, 0.9, 0.999)
+    }
+
+    pub fn with_betas(learning_rate: f32, beta1: f32, beta2: f32) -> Self {
+        assert!((0.0..1.0).contains(&beta1));
+        assert!((0.0..1.0).contains(&beta2));
+
+        Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon: 1e-8,
+            steps: 0,
+            means: Vec::new(),
+            variances: Vec::new(),
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, weights: &mut [f32], gradients: &[f32]) {
+        self.means.resize(weights.len(), 0.0);
+        self.variances.resize(weights.len(), 0.0);
+        self.steps += 1;
+
+        let mean_correction = 1.0 - self.beta1.powi(self.steps);
+        let variance_correction = 1.0 - self.beta2.powi(self.steps);
+
+        let moments = self.means.iter_mut().zip(&mut self.variances);
+
+        for ((weight, &gradient), (mean, variance)) in
+            weights.iter_mut().zip(gradients).zip(moments)
+        {
+            *mean = self.beta1 * *mean + (1.0 - self.beta1) * gradient;
+            *variance = self.beta2 * *variance + (1.0 - self.beta2) * gradient * gradient;
+
+            let mean = *mean / mean_correction;
+            let variance = *variance / variance_correction;
+
+            *weight -= self.learning_rate * mean / (variance.sqrt() + self.epsilon);
+        }
+    }
+}
+
+impl Network {
+    /// Runs the network on `inputs` and backpropagates the loss against
+    /// `targets`, returning the loss and its gradient with respect to each
+    /// weight (in the same order as `weights()` returns them).
+    ///
+    /// Recurrent layers aren't supported.
+    pub fn gradients(
+        &self,
+        inputs: &[f32],
+        targets: &[f32],
+        loss: Loss,
+    ) -> Result<(f32, Vec<f32>), NetworkError> {
+        // Inputs of each layer (plus outputs of the last one) and values of
+        // each neuron before the activation got applied
+        let mut activations = vec![inputs.to_vec()];
+        let mut sums = Vec::with_capacity(self.layers.len());
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            if layer.kind.is_recurrent() {
+                return Err(NetworkError::UnsupportedLayerKind {
+                    layer: layer_idx,
+                    kind: layer.kind,
+                });
+            }
+
+            let inputs = activations.last().unwrap();
+
+            if let Some(neuron) = layer
+                .neurons
+                .iter()
+                .find(|neuron| neuron.weights.len() != inputs.len())
+            {
+                return Err(NetworkError::InvalidInputSize {
+                    layer: layer_idx,
+                    expected: neuron.weights.len(),
+                    actual: inputs.len(),
+                });
+            }
+
+            let layer_sums: Vec<_> = layer
+                .neurons
+                .iter()
+                .map(|neuron| dot(inputs, &neuron.weights) + neuron.bias)
+                .collect();
+
+            let outputs = layer_sums
+                .iter()
+                .map(|&sum| layer.activation.apply(sum))
+                .collect();
+
+            sums.push(layer_sums);
+            activations.push(outputs);
+        }
+
+        let outputs = activations.last().unwrap();
+
+        if targets.len() != outputs.len() {
+            return Err(NetworkError::InvalidOutputSize {
+                expected: outputs.len(),
+                actual: targets.len(),
+            });
+        }
+
+        let loss_value = loss.loss(outputs, targets);
+        let mut upstream = loss.gradient(outputs, targets);
+        let mut layers_gradients = Vec::with_capacity(self.layers.len());
+
+        for (layer_idx, layer) in self.layers.iter().enumerate().rev() {
+            let inputs = &activations[layer_idx];
+            let mut gradients = Vec::with_capacity(layer.neurons.len() * (1 + inputs.len()));
+            let mut downstream = vec![0.0; inputs.len()];
+
+            for ((neuron, &sum), upstream) in
+                layer.neurons.iter().zip(&sums[layer_idx]).zip(&upstream)
+            {
+                let delta = upstream * layer.activation.derivative(sum);
+
+                gradients.push(delta);
+                gradients.extend(inputs.iter().map(|input| delta * input));
+
+                for (downstream, weight) in downstream.iter_mut().zip(&neuron.weights) {
+                    *downstream += delta * weight;
+                }
+            }
+
+            layers_gradients.push(gradients);
+            upstream = downstream;
+        }
+
+        let gradients = layers_gradients.into_iter().rev().flatten().collect();
+
+        Ok((loss_value, gradients))
+    }
+
+    /// Performs a single optimization step over given batch of
+    /// `(inputs, targets)` pairs, using their averaged gradients; returns
+    /// the average loss from before the step
+    pub fn train(
+        &mut self,
+        samples: &[(Vec<f32>, Vec<f32>)],
+        loss: Loss,
+        optimizer: &mut dyn Optimizer,
+    ) -> Result<f32, NetworkError> {
+        assert!(!samples.is_empty());
+
+        let mut total_loss = 0.0;
+        let mut total_gradients = vec![0.0; self.weights().count()];
+
+        for (inputs, targets) in samples {
+            let (loss, gradients) = self.gradients(inputs, targets, loss)?;
+
+            total_loss += loss;
+
+            for (total, gradient) in total_gradients.iter_mut().zip(gradients) {
+                *total += gradient;
+            }
+        }
+
+        let samples_count = samples.len() as f32;
+
+        for gradient in &mut total_gradients {
+            *gradient /= samples_count;
+        }
+
+        let mut weights: Vec<_> = self.weights().collect();
+        optimizer.step(&mut weights, &total_gradients);
+
+        for (weight, new_weight) in self.weights_mut().zip(weights) {
+            *weight = new_weight;
+        }
+
+        Ok(total_loss / samples_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::{Activation, LayerKind, LayerTopology};
+
+    fn assert_gradients_match(network: &Network, inputs: &[f32], targets: &[f32], loss: Loss) {
+        let (_, actual) = network.gradients(inputs, targets, loss).unwrap();
+        let weights: Vec<_> = network.weights().collect();
+        let topology = network.topology();
+
+        assert_eq!(actual.len(), weights.len());
+
+        for (idx, &actual) in actual.iter().enumerate() {
+            let h = 1e-3;
+
+            let loss_at = |delta: f32| {
+                let mut weights = weights.clone();
+                weights[idx] += delta;
+
+                let network = Network::from_weights(&topology, weights);
+                loss.loss(&network.propagate(inputs.to_vec()), targets)
+            };
+
+            let expected = (loss_at(h) - loss_at(-h)) / (2.0 * h);
+
+            approx::assert_relative_eq!(actual, expected, epsilon = 2e-3, max_relative = 2e-2);
+        }
+    }
+
+    #[test]
+    fn test_gradients_mean_squared_error() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let activations = [
+            Activation::LeakyRelu(0.1),
+            Activation::Sigmoid,
+            Activation::Tanh,
+            Activation::Identity,
+            Activation::Softsign,
+        ];
+
+        for &activation in &activations {
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(3),
+                    LayerTopology::new(4).with_activation(activation),
+                    LayerTopology::new(2).with_activation(Activation::Tanh),
+                ],
+            );
+
+            assert_gradients_match(
+                &network,
+                &[0.3, -0.7, 0.9],
+                &[0.5, -0.25],
+                Loss::MeanSquaredError,
+            );
+        }
+    }
+
+    #[test]
+    fn test_gradients_cross_entropy() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(5).with_activation(Activation::Tanh),
+                LayerTopology::new(2).with_activation(Activation::Sigmoid),
+            ],
+        );
+
+        assert_gradients_match(&network, &[0.3, -0.7, 0.9], &[1.0, 0.0], Loss::CrossEntropy);
+    }
+
+    #[test]
+    fn test_gradients_relu() {
+        let network = Network::from_weights(
+            &[LayerTopology::new(2), LayerTopology::new(2)],
+            vec![
+                0.5, 1.0, -1.0, // active
+                -0.5, 0.25, -1.0, // inactive
+            ],
+        );
+
+        let (loss, gradients) = network
+            .gradients(&[1.0, 0.5], &[0.0, 1.0], Loss::MeanSquaredError)
+            .unwrap();
+
+        // outputs: [1.0, 0.0]
+        approx::assert_relative_eq!(loss, 1.0);
+
+        approx::assert_relative_eq!(
+            gradients.as_slice(),
+            [1.0, 1.0, 0.5, 0.0, 0.0, 0.0].as_ref()
+        );
+    }
+
+    #[test]
+    fn test_loss() {
+        approx::assert_relative_eq!(Loss::MeanSquaredError.loss(&[1.0, 0.0], &[0.0, 0.0]), 0.5);
+
+        approx::assert_relative_eq!(
+            Loss::CrossEntropy.loss(&[0.5, 0.9], &[1.0, 1.0]),
+            -(0.5f32.ln() + 0.9f32.ln()) / 2.0
+        );
+    }
+
+    #[test]
+    fn test_recurrent_layers() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(1),
+                LayerTopology::new(1).with_kind(LayerKind::Elman),
+            ],
+            vec![0.0; 3],
+        );
+
+        assert_eq!(
+            network
+                .gradients(&[1.0], &[1.0], Loss::MeanSquaredError)
+                .unwrap_err(),
+            NetworkError::UnsupportedLayerKind {
+                layer: 0,
+                kind: LayerKind::Elman,
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_targets() {
+        let network = Network::from_weights(
+            &[LayerTopology::new(1), LayerTopology::new(1)],
+            vec![0.0; 2],
+        );
+
+        assert_eq!(
+            network
+                .gradients(&[1.0], &[1.0, 2.0], Loss::MeanSquaredError)
+                .unwrap_err(),
+            NetworkError::InvalidOutputSize {
+                expected: 1,
+                actual: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_sgd() {
+        let mut weights = [1.0, 2.0];
+        let mut sgd = Sgd::with_momentum(0.1, 0.5);
+
+        sgd.step(&mut weights, &[0.5, -1.0]);
+        approx::assert_relative_eq!(weights.as_ref(), [0.95, 2.1].as_ref());
+
+        sgd.step(&mut weights, &[0.5, -1.0]);
+        approx::assert_relative_eq!(weights.as_ref(), [0.875, 2.25].as_ref());
+    }
+
+    #[test]
+    fn test_adam() {
+        let mut weights = [1.0, 2.0];
+        let mut adam = Adam::new(0.1);
+
+        // First step moves each weight by (almost exactly) the learning rate
+        adam.step(&mut weights, &[0.5, -3.0]);
+        approx::assert_relative_eq!(weights.as_ref(), [0.9, 2.1].as_ref(), epsilon = 1e-6);
+    }
+
+    fn samples(rng: &mut ChaCha8Rng) -> Vec<(Vec<f32>, Vec<f32>)> {
+        (0..32)
+            .map(|_| {
+                let a: f32 = rng.gen_range(-1.0..=1.0);
+                let b: f32 = rng.gen_range(-1.0..=1.0);
+
+                (vec![a, b], vec![0.5 * a - 0.3 * b, (a * b).tanh()])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_train_sgd() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let samples = samples(&mut rng);
+
+        let mut network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(8).with_activation(Activation::Tanh),
+                LayerTopology::new(2).with_activation(Activation::Identity),
+            ],
+        );
+
+        let mut sgd = Sgd::with_momentum(0.1, 0.9);
+
+        let initial = network
+            .train(&samples, Loss::MeanSquaredError, &mut sgd)
+            .unwrap();
+
+        let last = (0..500)
+            .map(|_| {
+                network
+                    .train(&samples, Loss::MeanSquaredError, &mut sgd)
+                    .unwrap()
+            })
+            .last()
+            .unwrap();
+
+        assert!(last < initial / 10.0, "{} vs {}", last, initial);
+    }
+
+    #[test]
+    fn test_train_adam() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let samples = samples(&mut rng);
+
+        let mut network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(8).with_activation(Activation::Tanh),
+                LayerTopology::new(2).with_activation(Activation::Identity),
+            ],
+        );
+
+        let mut adam = Adam::new(0.01);
+
+        let initial = network
+            .train(&samples, Loss::MeanSquaredError, &mut adam)
+            .unwrap();
+
+        let last = (0..500)
+            .map(|_| {
+                network
+                    .train(&samples, Loss::MeanSquaredError, &mut adam)
+                    .unwrap()
+            })
+            .last()
+            .unwrap();
+
+        assert!(last < initial / 10.0, "{} vs {}", last, initial);
+    }
+}
@@ -40,6 +40,28 @@ average = {:.2}",
             stats.average_fitness()
         )
     }
+
+    /// Fittest brain so far rendered to SVG, or `undefined` before the
+    /// first generation has been evolved
+    pub fn best_brain_svg(&self) -> Option<String> {
+        self.sim.best_brain().map(|brain| brain.to_svg(0.0))
+    }
+
+    /// Fittest brain so far serialized with `Network::to_json()`, e.g. to be
+    /// saved and loaded back later; `undefined` before the first generation
+    /// has been evolved
+    pub fn best_brain_json(&self) -> Option<String> {
+        self.sim
+            .best_brain()
+            .map(|brain| brain.network().to_json().unwrap())
+    }
+
+    /// Same as `best_brain_json()`, but serialized with `Network::to_bytes()`
+    pub fn best_brain_bytes(&self) -> Option<Vec<u8>> {
+        self.sim
+            .best_brain()
+            .map(|brain| brain.network().to_bytes().unwrap())
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -52,6 +52,10 @@ impl Animal {
         self.speed
     }
 
+    pub fn brain(&self) -> &Brain {
+        &self.brain
+    }
+
     pub fn as_chromosome(&self) -> Chromosome {
         self.brain.as_chromosome()
     }
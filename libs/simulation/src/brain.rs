@@ -1,5 +1,5 @@
 use lib_genetic_algorithm::chromosome::Chromosome;
-use lib_neural_network::{LayerTopology, Network};
+use lib_neural_network::{LayerTopology, Network, RenderOptions};
 use rand::RngCore;
 
 use crate::Eye;
@@ -16,6 +16,21 @@ impl Brain {
         }
     }
 
+    pub fn network(&self) -> &Network {
+        &self.nn
+    }
+
+    /// Renders this brain to DOT, labelling inputs with eye cells and outputs
+    /// with what they control
+    pub fn to_dot(&self, min_weight: f32) -> String {
+        self.nn.to_dot(&self.render_options(min_weight))
+    }
+
+    /// Same as `to_dot()`, but renders to SVG
+    pub fn to_svg(&self, min_weight: f32) -> String {
+        self.nn.to_svg(&self.render_options(min_weight))
+    }
+
     crate fn as_chromosome(&self) -> Chromosome {
         self.nn.weights().collect()
     }
@@ -32,16 +47,20 @@ impl Brain {
         }
     }
 
+    fn render_options(&self, min_weight: f32) -> RenderOptions {
+        let cells = self.nn.topology()[0].neurons;
+
+        RenderOptions::default()
+            .with_min_weight(min_weight)
+            .with_input_labels((0..cells).map(|cell| format!("eye {}", cell)))
+            .with_output_labels(vec!["speed", "rotation"])
+    }
 
     fn topology(eye: &Eye) -> [LayerTopology; 3] {
         [
-            LayerTopology {
-                neurons: eye.cells(),
-            },
-            LayerTopology {
-                neurons: 2 * eye.cells(),
-            },
-            LayerTopology { neurons: 2 },
+            LayerTopology::new(eye.cells()),
+            LayerTopology::new(2 * eye.cells()),
+            LayerTopology::new(2),
         ]
     }
 }
@@ -14,7 +14,7 @@ use animal_individual::AnimalIndividual;
 pub use brain::Brain;
 pub use eye::Eye;
 pub use food::Food;
-use ga::{GeneticAlgorithm, gaussian_mutation::GaussianMutation, roulette_wheel::RouletteWheelSelection, statistics::Statistics, uniform_crossover::UniformCrossover};
+use ga::{GeneticAlgorithm, gaussian_mutation::GaussianMutation, hall_of_fame::HallOfFame, roulette_wheel::RouletteWheelSelection, statistics::Statistics, uniform_crossover::UniformCrossover};
 use lib_genetic_algorithm as ga;
 use na::{Rotation2, Vector2};
 use nalgebra as na;
@@ -27,6 +27,7 @@ const SPEED_MAX: f32 = 0.005;
 const SPEED_ACCEL: f32 = 0.2;
 const ROTATION_ACCEL: f32 = FRAC_PI_2;
 const GENERATION_LENGTH: usize = 2500;
+const HALL_OF_FAME_SIZE: usize = 10;
 
 pub struct Simulation {
     world: World,
@@ -42,7 +43,8 @@ impl Simulation {
             RouletteWheelSelection::new(),
             UniformCrossover::new(),
             GaussianMutation::new(0.01, 0.3),
-        );
+        )
+        .with_hall_of_fame(HALL_OF_FAME_SIZE);
         Self { world, ga, age: 0 }
     }
 
@@ -50,6 +52,18 @@ impl Simulation {
         &self.world
     }
 
+    /// Fittest brains of all the generations evolved so far
+    pub fn hall_of_fame(&self) -> &HallOfFame {
+        self.ga.hall_of_fame().expect("hall of fame is always enabled")
+    }
+
+    /// Fittest brain of all the generations evolved so far, if any
+    pub fn best_brain(&self) -> Option<Brain> {
+        self.hall_of_fame()
+            .best()
+            .map(|champion| Brain::from_chromosome(champion.chromosome().clone(), &Eye::default()))
+    }
+
     pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<Statistics>{
         self.process_collisions(rng);
         self.process_brain();